@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use tauri_utils::acl::{self, build};
+
+/// Every command of `commands.rs`, each one gets an `allow-` and a `deny-` permission.
+const COMMANDS: &[&str] = &[
+    "show",
+    "hide",
+    "toggle",
+    "close",
+    "set_level",
+    "set_alpha",
+    "set_frame",
+    "is_visible",
+    "show_animated",
+    "hide_animated",
+];
+
+/// The scope of the panel commands, mirrors `PanelScope` in `src/scope.rs`.
+#[derive(schemars::JsonSchema)]
+#[allow(dead_code)]
+struct PanelScope {
+    /// The label of a panel the commands may drive, `*` matches any run of characters.
+    label: String,
+}
+
+fn main() {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let autogenerated = Path::new("permissions").join(build::AUTOGENERATED_FOLDER_NAME);
+
+    build::autogenerate_command_permissions(&autogenerated.join("commands"), COMMANDS, "", true);
+    println!("cargo:rerun-if-changed=permissions");
+
+    // The app's build script finds our permissions through the `links` key
+    let permissions = build::define_permissions(
+        "./permissions/**/*.*",
+        "tauri-plugin-nspanel",
+        &out_dir,
+        |_| true,
+    )
+    .expect("failed to define the plugin permissions");
+
+    acl::schema::generate_permissions_schema(&permissions, "./permissions")
+        .expect("failed to generate the permissions schema");
+    build::generate_docs(&permissions, &autogenerated, "nspanel")
+        .expect("failed to generate the permissions reference");
+
+    build::define_global_scope_schema(
+        schemars::schema_for!(PanelScope),
+        "tauri-plugin-nspanel",
+        &out_dir,
+    )
+    .expect("failed to define the scope schema");
+}
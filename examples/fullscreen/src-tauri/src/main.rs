@@ -4,7 +4,10 @@
 )]
 
 use tauri::{AppHandle, Manager, WebviewWindow};
-use tauri_nspanel::{panel_delegate, ManagerExt, WebviewWindowExt, cocoa::appkit::NSWindowCollectionBehavior};
+use tauri_nspanel::{
+  panel_delegate, CollectionBehavior, ManagerExt, PanelEvent, PanelLevel, PanelStyleMask,
+  WebviewWindowExt,
+};
 
 fn main() {
   tauri::Builder::default()
@@ -38,14 +41,14 @@ fn init(app_handle: &AppHandle) {
 
   let handle = app_handle.to_owned();
 
-  delegate.set_listener(Box::new(move |delegate_name: String| {
-    match delegate_name.as_str() {
-      "window_did_become_key" => {
+  delegate.set_listener(Box::new(move |event: PanelEvent| {
+    match event {
+      PanelEvent::DidBecomeKey => {
         let app_name = handle.package_info().name.to_owned();
 
         println!("[info]: {:?} panel becomes key window!", app_name);
       }
-      "window_did_resign_key" => {
+      PanelEvent::DidResignKey => {
         println!("[info]: panel resigned from key window!");
       }
       _ => (),
@@ -53,21 +56,16 @@ fn init(app_handle: &AppHandle) {
   }));
 
   // Set the window to float level
-  #[allow(non_upper_case_globals)]
-  const NSFloatWindowLevel: i32 = 4;
-  panel.set_level(NSFloatWindowLevel);
+  panel.set_level(PanelLevel::Floating);
 
-  #[allow(non_upper_case_globals)]
-  const NSWindowStyleMaskNonActivatingPanel: i32 = 1 << 7;
   // Ensures the panel cannot activate the app
-  panel.set_style_mask(NSWindowStyleMaskNonActivatingPanel);
+  panel.set_style_mask(PanelStyleMask::NONACTIVATING_PANEL).unwrap();
 
   // Allows the panel to:
   // - display on the same space as the full screen window
   // - join all spaces
   panel.set_collection_behaviour(
-    NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary |
-    NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
+    CollectionBehavior::FULL_SCREEN_AUXILIARY | CollectionBehavior::CAN_JOIN_ALL_SPACES
   );
 
   panel.set_delegate(delegate);
@@ -84,7 +82,7 @@ fn show_panel(handle: AppHandle) {
 fn hide_panel(handle: AppHandle) {
   let panel = handle.get_webview_panel("main").unwrap();
 
-  panel.order_out(None);
+  panel.order_out();
 }
 
 #[tauri::command]
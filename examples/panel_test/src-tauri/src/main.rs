@@ -136,7 +136,7 @@ fn show_panel(handle: AppHandle) {
 #[tauri::command]
 fn hide_panel(handle: AppHandle) {
     let panel = handle.get_webview_panel("main").unwrap();
-    panel.order_out(None);
+    panel.order_out();
 }
 
 #[tauri::command]
@@ -148,21 +148,21 @@ fn close_panel(handle: AppHandle) {
 
 #[tauri::command]
 fn show_overlay(handle: AppHandle, label: &str) {
-    if let Ok(panel) = handle.get_webview_overlay_panel(label) {
+    if let Ok(panel) = handle.get_webview_panel(label) {
         panel.show();
     }
 }
 
 #[tauri::command]
 fn hide_overlay(handle: AppHandle, label: &str) {
-    if let Ok(panel) = handle.get_webview_overlay_panel(label) {
-        panel.order_out(None);
+    if let Ok(panel) = handle.get_webview_panel(label) {
+        panel.order_out();
     }
 }
 
 #[tauri::command]
 fn close_overlay(handle: AppHandle, label: &str) {
-    if let Ok(panel) = handle.get_webview_overlay_panel(label) {
+    if let Ok(panel) = handle.get_webview_panel(label) {
         panel.released_when_closed(true);
         panel.close();
     }
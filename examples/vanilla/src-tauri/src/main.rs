@@ -4,7 +4,7 @@
 )]
 
 use tauri::{AppHandle, Manager, WebviewWindow};
-use tauri_nspanel::{panel_delegate, ManagerExt, WebviewWindowExt};
+use tauri_nspanel::{panel_delegate, ManagerExt, PanelEvent, WebviewWindowExt};
 
 fn main() {
   tauri::Builder::default()
@@ -35,14 +35,14 @@ fn init(app_handle: &AppHandle) {
 
   let handle = app_handle.to_owned();
 
-  delegate.set_listener(Box::new(move |delegate_name: String| {
-    match delegate_name.as_str() {
-      "window_did_become_key" => {
+  delegate.set_listener(Box::new(move |event: PanelEvent| {
+    match event {
+      PanelEvent::DidBecomeKey => {
         let app_name = handle.package_info().name.to_owned();
 
         println!("[info]: {:?} panel becomes key window!", app_name);
       }
-      "window_did_resign_key" => {
+      PanelEvent::DidResignKey => {
         println!("[info]: panel resigned from key window!");
       }
       _ => (),
@@ -63,7 +63,7 @@ fn show_panel(handle: AppHandle) {
 fn hide_panel(handle: AppHandle) {
   let panel = handle.get_webview_panel("main").unwrap();
 
-  panel.order_out(None);
+  panel.order_out();
 }
 
 #[tauri::command]
@@ -0,0 +1,176 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+
+/** A named `NSWindowLevel`, or a raw level number. */
+export type PanelLevel =
+  | "normal"
+  | "floating"
+  | "modal"
+  | "dock"
+  | "mainMenu"
+  | "status"
+  | "popUpMenu"
+  | "screenSaver"
+  | "shielding"
+  | number;
+
+/** A point in AppKit screen coordinates, with the origin at the bottom left of the main screen. */
+export interface Point {
+  x: number;
+  y: number;
+}
+
+export interface Size {
+  width: number;
+  height: number;
+}
+
+/** A rectangle in AppKit screen coordinates, see {@link Point}. */
+export interface Rect {
+  origin: Point;
+  size: Size;
+}
+
+/**
+ * The screen a panel is shown on: the one under the mouse, the one of the key window, the one
+ * holding the menu bar, or the screen with the given id.
+ */
+export type ShowTarget = "mouse" | "keyWindow" | "mainScreen" | { screen: number };
+
+/** An edge of the screen a panel slides in from. */
+export type Edge = "top" | "bottom" | "left" | "right";
+
+/** How a panel appears and disappears, every transition also fades it. */
+export type Transition = "fade" | "scale" | { slide: Edge };
+
+export type Easing = "linear" | "easeIn" | "easeOut" | "easeInOut";
+
+export interface Animation {
+  transition: Transition;
+  /** In milliseconds, 200 by default. */
+  duration?: number;
+  /** `"easeInOut"` by default. */
+  easing?: Easing;
+}
+
+/** What the panel commands reject with, `kind` names the Rust `Error` variant. */
+export interface PanelError {
+  kind:
+    | "PanelNotFound"
+    | "WindowNotFound"
+    | "AlreadyConverted"
+    | "NativeHandleUnavailable"
+    | "NotOnMainThread"
+    | "MainThreadUnavailable"
+    | "StorePoisoned"
+    | "WrongPanelKind"
+    | "PanelNotAllowed"
+    | "InvalidStyleMask"
+    | "ScreenUnavailable";
+  message: string;
+}
+
+/** A panel created by the plugin, driven from the frontend. */
+export class Panel {
+  /** The label of the panel's window. */
+  readonly label: string;
+
+  constructor(label: string) {
+    this.label = label;
+  }
+
+  /**
+   * Returns the panel of the window with the given label, or `null` if there is none.
+   *
+   * The panel is looked up with `isVisible`, so the capability must allow
+   * `nspanel:allow-is-visible`, part of `nspanel:default`, for this label. Otherwise the
+   * lookup rejects, with a `PanelNotAllowed` error when the label is outside the command's
+   * scope, even if the panel exists.
+   */
+  static async getByLabel(label: string): Promise<Panel | null> {
+    const panel = new Panel(label);
+
+    try {
+      await panel.isVisible();
+    } catch (error) {
+      if ((error as PanelError | undefined)?.kind === "PanelNotFound") {
+        return null;
+      }
+
+      throw error;
+    }
+
+    return panel;
+  }
+
+  /**
+   * Orders the panel front and makes it key.
+   *
+   * With a `target`, the panel is first moved to that screen, keeping its place on the screen.
+   */
+  async show(target?: ShowTarget): Promise<void> {
+    await invoke("plugin:nspanel|show", { label: this.label, target });
+  }
+
+  /** Orders the panel out, it can be shown again. */
+  async hide(): Promise<void> {
+    await invoke("plugin:nspanel|hide", { label: this.label });
+  }
+
+  /**
+   * Shows the panel with a transition, fading it in by default.
+   *
+   * Resolves once the transition is over, to `false` if another transition interrupted it.
+   */
+  async showAnimated(animation?: Animation): Promise<boolean> {
+    return await invoke("plugin:nspanel|show_animated", { label: this.label, animation });
+  }
+
+  /**
+   * Hides the panel with a transition, fading it out by default.
+   *
+   * Resolves once the transition is over, to `false` if another transition interrupted it.
+   */
+  async hideAnimated(animation?: Animation): Promise<boolean> {
+    return await invoke("plugin:nspanel|hide_animated", { label: this.label, animation });
+  }
+
+  /** Shows the panel if it is hidden and hides it otherwise, resolves to whether it is now visible. */
+  async toggle(): Promise<boolean> {
+    return await invoke("plugin:nspanel|toggle", { label: this.label });
+  }
+
+  async close(): Promise<void> {
+    await invoke("plugin:nspanel|close", { label: this.label });
+  }
+
+  async setLevel(level: PanelLevel): Promise<void> {
+    await invoke("plugin:nspanel|set_level", { label: this.label, level });
+  }
+
+  async setAlpha(alpha: number): Promise<void> {
+    await invoke("plugin:nspanel|set_alpha", { label: this.label, alpha });
+  }
+
+  /**
+   * Moves and resizes the panel, `frame` is in AppKit screen coordinates.
+   *
+   * With `animate` the promise resolves once the panel reached its new frame.
+   */
+  async setFrame(frame: Rect, animate = false): Promise<void> {
+    await invoke("plugin:nspanel|set_frame", {
+      label: this.label,
+      frame,
+      animate,
+    });
+  }
+
+  async isVisible(): Promise<boolean> {
+    return await invoke("plugin:nspanel|is_visible", { label: this.label });
+  }
+}
+
+/** Returns the panel of the current webview window. */
+export function getCurrentPanel(): Panel {
+  return new Panel(getCurrentWebviewWindow().label);
+}
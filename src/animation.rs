@@ -0,0 +1,386 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{backend::PanelBackend, Edge, Rect};
+
+/// How long a transition lasts unless [`Animation::duration`] says otherwise.
+pub const DEFAULT_DURATION: Duration = Duration::from_millis(200);
+
+/// The size a [`Transition::Scale`] grows from and shrinks to, relative to the panel's size.
+const SCALE: f64 = 0.9;
+
+/// How a panel appears in [`Panel::show_animated`](crate::Panel::show_animated) and disappears
+/// in [`Panel::hide_animated`](crate::Panel::hide_animated).
+///
+/// Every transition fades the panel in and out, the others also move or resize it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Transition {
+    #[default]
+    Fade,
+    /// Slides the panel in from the given edge of its screen's visible frame, and back out.
+    Slide(Edge),
+    /// Grows the panel from slightly smaller than its size, around its center.
+    Scale,
+}
+
+/// The pace of a transition, AppKit's `CAMediaTimingFunction` names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// The name of the matching `CAMediaTimingFunction`.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn timing_function_name(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::EaseIn => "easeIn",
+            Self::EaseOut => "easeOut",
+            Self::EaseInOut => "easeInEaseOut",
+        }
+    }
+}
+
+/// A transition with its duration and easing.
+///
+/// From the guest JavaScript API, animations are given as
+/// `{ transition, duration?, easing? }` with the duration in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Animation {
+    pub transition: Transition,
+    #[serde(default = "default_duration", deserialize_with = "millis")]
+    pub duration: Duration,
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+impl Animation {
+    pub fn new(transition: Transition) -> Self {
+        Self {
+            transition,
+            duration: DEFAULT_DURATION,
+            easing: Easing::default(),
+        }
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::new(Transition::default())
+    }
+}
+
+fn default_duration() -> Duration {
+    DEFAULT_DURATION
+}
+
+fn millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_millis)
+}
+
+/// Where `transition` starts a panel resting at `frame` when showing it, and leaves it when
+/// hiding it. `screen` is the visible frame of the panel's screen, if it has one.
+pub(crate) fn hidden_frame(transition: Transition, frame: Rect, screen: Option<Rect>) -> Rect {
+    match transition {
+        Transition::Fade => frame,
+        Transition::Slide(edge) => {
+            // Without a screen, the panel slides by its own size
+            let screen = screen.unwrap_or(frame);
+            let mut hidden = frame;
+
+            match edge {
+                Edge::Top => hidden.origin.y = screen.max_y(),
+                Edge::Bottom => hidden.origin.y = screen.origin.y - frame.size.height,
+                Edge::Left => hidden.origin.x = screen.origin.x - frame.size.width,
+                Edge::Right => hidden.origin.x = screen.max_x(),
+            }
+
+            hidden
+        }
+        Transition::Scale => {
+            let width = frame.size.width * SCALE;
+            let height = frame.size.height * SCALE;
+
+            Rect::new(
+                frame.origin.x + (frame.size.width - width) / 2.0,
+                frame.origin.y + (frame.size.height - height) / 2.0,
+                width,
+                height,
+            )
+        }
+    }
+}
+
+/// The frame and alpha a panel rests at once its transitions are over.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Resting {
+    pub(crate) frame: Rect,
+    pub(crate) alpha: f64,
+}
+
+/// The transitions of a panel, shared by every handle to the panel.
+///
+/// Only the latest transition finishes: the ones it interrupted leave the panel alone when
+/// AppKit completes them, so a hide interrupting a show neither leaves the panel half
+/// transparent nor gets undone by the show.
+#[derive(Clone, Default)]
+pub(crate) struct Animations(Arc<Mutex<AnimationsInner>>);
+
+#[derive(Default)]
+struct AnimationsInner {
+    generation: u64,
+    /// Where the panel rests, while a transition is running.
+    resting: Option<Resting>,
+}
+
+impl Animations {
+    fn lock(&self) -> MutexGuard<'_, AnimationsInner> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Starts a transition, interrupting the running one, and returns its generation and
+    /// where the panel rests.
+    ///
+    /// A panel is at rest where it is unless a transition is running.
+    pub(crate) fn begin(&self, panel: &dyn PanelBackend) -> (u64, Resting) {
+        let mut inner = self.lock();
+        inner.generation += 1;
+
+        let resting = *inner.resting.get_or_insert_with(|| Resting {
+            frame: panel.frame(),
+            alpha: panel.alpha_value(),
+        });
+
+        (inner.generation, resting)
+    }
+
+    /// Whether a transition is running.
+    pub(crate) fn is_running(&self) -> bool {
+        self.lock().resting.is_some()
+    }
+
+    /// Ends the transition of `generation`, returns where the panel rests unless a later
+    /// transition interrupted it.
+    pub(crate) fn end(&self, generation: u64) -> Option<Resting> {
+        let mut inner = self.lock();
+
+        if inner.generation == generation {
+            inner.resting.take()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Rect = Rect {
+        origin: crate::Point { x: 570.0, y: 350.0 },
+        size: crate::Size {
+            width: 300.0,
+            height: 200.0,
+        },
+    };
+
+    const SCREEN: Rect = Rect {
+        origin: crate::Point { x: 0.0, y: 0.0 },
+        size: crate::Size {
+            width: 1440.0,
+            height: 875.0,
+        },
+    };
+
+    #[test]
+    fn fading_keeps_the_frame() {
+        assert_eq!(hidden_frame(Transition::Fade, FRAME, Some(SCREEN)), FRAME);
+    }
+
+    #[test]
+    fn panels_slide_from_the_edge_of_their_screen() {
+        let hidden = |edge| hidden_frame(Transition::Slide(edge), FRAME, Some(SCREEN));
+
+        assert_eq!(hidden(Edge::Top), Rect::new(570.0, 875.0, 300.0, 200.0));
+        assert_eq!(hidden(Edge::Bottom), Rect::new(570.0, -200.0, 300.0, 200.0));
+        assert_eq!(hidden(Edge::Left), Rect::new(-300.0, 350.0, 300.0, 200.0));
+        assert_eq!(hidden(Edge::Right), Rect::new(1440.0, 350.0, 300.0, 200.0));
+    }
+
+    #[test]
+    fn panels_without_screen_slide_by_their_size() {
+        assert_eq!(
+            hidden_frame(Transition::Slide(Edge::Top), FRAME, None),
+            Rect::new(570.0, 550.0, 300.0, 200.0)
+        );
+    }
+
+    #[test]
+    fn panels_scale_around_their_center() {
+        assert_eq!(
+            hidden_frame(Transition::Scale, FRAME, Some(SCREEN)),
+            Rect::new(585.0, 360.0, 270.0, 180.0)
+        );
+    }
+
+    #[test]
+    fn animations_deserialize_with_defaults() {
+        let animation = |value| serde_json::from_value::<Animation>(value).unwrap();
+
+        assert_eq!(
+            animation(serde_json::json!({ "transition": "fade" })),
+            Animation::new(Transition::Fade)
+        );
+        assert_eq!(
+            animation(serde_json::json!({
+                "transition": { "slide": "top" },
+                "duration": 350,
+                "easing": "easeOut",
+            })),
+            Animation::new(Transition::Slide(Edge::Top))
+                .duration(Duration::from_millis(350))
+                .easing(Easing::EaseOut)
+        );
+    }
+
+    /// A panel of `app` resting at `frame`, hidden, and the outcomes of its transitions.
+    #[cfg(feature = "test-util")]
+    fn animated_panel(
+        app: &tauri::App<tauri::test::MockRuntime>,
+        frame: Rect,
+    ) -> (
+        crate::Panel,
+        Arc<crate::test_util::FakePanel>,
+        Arc<Mutex<Vec<bool>>>,
+    ) {
+        use crate::{test_util, testing::window, WebviewWindowExt};
+
+        let panel = window(app, "hud").to_panel().unwrap();
+        let fake = test_util::fake_panel(app, "hud").unwrap();
+        panel.order_out();
+        panel.set_frame(frame, false);
+        panel.set_alpha_value(0.9);
+
+        (panel, fake, Arc::default())
+    }
+
+    #[cfg(feature = "test-util")]
+    fn record(outcomes: &Arc<Mutex<Vec<bool>>>) -> impl FnOnce(bool) + Send + 'static {
+        let outcomes = outcomes.clone();
+        move |finished| outcomes.lock().unwrap().push(finished)
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn panels_are_shown_and_hidden_with_transitions() {
+        use crate::{test_util, testing::app};
+
+        let app = app(test_util::init());
+        let frame = Rect::new(570.0, 350.0, 300.0, 200.0);
+        let (panel, fake, outcomes) = animated_panel(&app, frame);
+        let animation = Animation::new(Transition::Slide(Edge::Top))
+            .duration(Duration::from_millis(300))
+            .easing(Easing::EaseOut);
+
+        panel.show_animated(animation, record(&outcomes)).unwrap();
+
+        // Shown transparent above the top of the panel, then slid down into place
+        fake.assert_visible();
+        fake.assert_called(&test_util::PanelCall::SetFrame(
+            Rect::new(570.0, 550.0, 300.0, 200.0),
+            false,
+        ));
+        fake.assert_called(&test_util::PanelCall::SetAlphaValue(0.0));
+        fake.assert_called(&test_util::PanelCall::Animate(
+            frame,
+            0.9,
+            Duration::from_millis(300),
+            Easing::EaseOut,
+        ));
+        assert!(outcomes.lock().unwrap().is_empty());
+
+        fake.finish_animations();
+        assert_eq!(*outcomes.lock().unwrap(), [true]);
+
+        panel
+            .hide_animated(Animation::new(Transition::Scale), record(&outcomes))
+            .unwrap();
+        fake.finish_animations();
+
+        fake.assert_hidden();
+        fake.assert_alpha(0.9);
+        assert_eq!(panel.frame(), frame);
+        assert_eq!(*outcomes.lock().unwrap(), [true, true]);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn hides_interrupting_shows_leave_the_panel_opaque() {
+        use crate::{test_util, testing::app};
+
+        let app = app(test_util::init());
+        let frame = Rect::new(570.0, 350.0, 300.0, 200.0);
+        let (panel, fake, outcomes) = animated_panel(&app, frame);
+
+        panel
+            .show_animated(Animation::default(), record(&outcomes))
+            .unwrap();
+        panel
+            .hide_animated(Animation::default(), record(&outcomes))
+            .unwrap();
+        fake.finish_animations();
+
+        fake.assert_hidden();
+        fake.assert_alpha(0.9);
+        assert_eq!(panel.frame(), frame);
+        assert_eq!(*outcomes.lock().unwrap(), [false, true]);
+
+        // Shown again without animation, the panel is as it was
+        panel.show();
+        fake.assert_visible();
+        fake.assert_alpha(0.9);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn shows_interrupting_hides_keep_the_panel_visible() {
+        use crate::{test_util, testing::app};
+
+        let app = app(test_util::init());
+        let frame = Rect::new(570.0, 350.0, 300.0, 200.0);
+        let (panel, fake, outcomes) = animated_panel(&app, frame);
+        panel.show();
+
+        let slide = Animation::new(Transition::Slide(Edge::Right));
+        panel.hide_animated(slide, record(&outcomes)).unwrap();
+        panel.show_animated(slide, record(&outcomes)).unwrap();
+        fake.finish_animations();
+
+        fake.assert_visible();
+        fake.assert_alpha(0.9);
+        assert_eq!(panel.frame(), frame);
+        assert_eq!(*outcomes.lock().unwrap(), [false, true]);
+    }
+}
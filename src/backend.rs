@@ -0,0 +1,249 @@
+#[cfg(target_os = "macos")]
+use cocoa::base::id;
+#[cfg(target_os = "macos")]
+use objc::Message;
+#[cfg(target_os = "macos")]
+use objc_id::ShareId;
+
+use std::time::Duration;
+
+use crate::{CollectionBehavior, Easing, PanelLevel, PanelStyleMask, Point, Rect, Screen};
+
+/// The operations the plugin needs from a native panel.
+///
+/// On macOS this is implemented by [`RawPanel`](crate::raw_panel::RawPanel). Everywhere else
+/// panels are backed by [`MemoryPanel`](crate::memory_panel::MemoryPanel), which only tracks state so the
+/// plugin can be exercised without AppKit.
+pub trait PanelBackend: Send + Sync {
+    fn show(&self);
+
+    fn is_visible(&self) -> bool;
+
+    /// Overrides the kind's [`CAN_BECOME_KEY_WINDOW`](crate::PanelKind::CAN_BECOME_KEY_WINDOW)
+    /// for this panel.
+    fn set_can_become_key_window(&self, flag: bool);
+
+    /// Overrides the kind's [`CAN_BECOME_MAIN_WINDOW`](crate::PanelKind::CAN_BECOME_MAIN_WINDOW)
+    /// for this panel.
+    fn set_can_become_main_window(&self, flag: bool);
+
+    fn make_key_window(&self);
+
+    fn resign_key_window(&self);
+
+    fn order_front_regardless(&self);
+
+    fn order_front(&self);
+
+    fn order_out(&self);
+
+    fn set_level(&self, level: PanelLevel);
+
+    fn level(&self) -> PanelLevel;
+
+    fn set_alpha_value(&self, value: f64);
+
+    fn alpha_value(&self) -> f64;
+
+    fn set_content_size(&self, width: f64, height: f64);
+
+    /// Moves and resizes the panel, `frame` is in AppKit screen coordinates.
+    ///
+    /// With `animate` the panel is animated to its new frame, and the call returns once the
+    /// animation is over.
+    fn set_frame(&self, frame: Rect, animate: bool);
+
+    /// The panel's frame, in AppKit screen coordinates.
+    fn frame(&self) -> Rect;
+
+    /// Animates the panel's frame and alpha to `frame` and `alpha`, then calls `completion`
+    /// on the main thread.
+    ///
+    /// Returns right away. An animation started before this one is over is interrupted, its
+    /// completion is still called.
+    fn animate(
+        &self,
+        frame: Rect,
+        alpha: f64,
+        duration: Duration,
+        easing: Easing,
+        completion: Box<dyn FnOnce() + Send>,
+    );
+
+    /// The screen showing most of the panel, `None` while it is off screen.
+    fn screen(&self) -> Option<Screen>;
+
+    /// Every connected screen, the one holding the menu bar first.
+    fn screens(&self) -> Vec<Screen>;
+
+    /// The mouse cursor's location, in AppKit screen coordinates.
+    fn mouse_location(&self) -> Point;
+
+    /// The screen of the app's key window, `None` without key window.
+    fn key_window_screen(&self) -> Option<Screen>;
+
+    /// Sets the style mask as is, [`Panel::set_style_mask`](crate::Panel::set_style_mask)
+    /// validates it first.
+    fn set_style_mask(&self, style_mask: PanelStyleMask);
+
+    fn style_mask(&self) -> PanelStyleMask;
+
+    fn set_collection_behaviour(&self, behaviour: CollectionBehavior);
+
+    fn set_floating_panel(&self, flag: bool);
+
+    fn set_accepts_mouse_moved_events(&self, flag: bool);
+
+    fn set_ignores_mouse_events(&self, flag: bool);
+
+    fn set_hides_on_deactivate(&self, flag: bool);
+
+    fn set_movable_by_window_background(&self, flag: bool);
+
+    /// Sets the window's delegate, which the panel keeps alive until it is deallocated or
+    /// given another delegate.
+    #[cfg(target_os = "macos")]
+    fn set_delegate(&self, delegate: id);
+
+    fn released_when_closed(&self, flag: bool);
+
+    fn close(&self);
+}
+
+#[cfg(target_os = "macos")]
+impl<T: PanelBackend + Message> PanelBackend for ShareId<T> {
+    fn show(&self) {
+        (**self).show()
+    }
+
+    fn is_visible(&self) -> bool {
+        (**self).is_visible()
+    }
+
+    fn set_can_become_key_window(&self, flag: bool) {
+        (**self).set_can_become_key_window(flag)
+    }
+
+    fn set_can_become_main_window(&self, flag: bool) {
+        (**self).set_can_become_main_window(flag)
+    }
+
+    fn make_key_window(&self) {
+        (**self).make_key_window()
+    }
+
+    fn resign_key_window(&self) {
+        (**self).resign_key_window()
+    }
+
+    fn order_front_regardless(&self) {
+        (**self).order_front_regardless()
+    }
+
+    fn order_front(&self) {
+        (**self).order_front()
+    }
+
+    fn order_out(&self) {
+        (**self).order_out()
+    }
+
+    fn set_level(&self, level: PanelLevel) {
+        (**self).set_level(level)
+    }
+
+    fn level(&self) -> PanelLevel {
+        (**self).level()
+    }
+
+    fn set_alpha_value(&self, value: f64) {
+        (**self).set_alpha_value(value)
+    }
+
+    fn alpha_value(&self) -> f64 {
+        (**self).alpha_value()
+    }
+
+    fn set_content_size(&self, width: f64, height: f64) {
+        (**self).set_content_size(width, height)
+    }
+
+    fn set_frame(&self, frame: Rect, animate: bool) {
+        (**self).set_frame(frame, animate)
+    }
+
+    fn frame(&self) -> Rect {
+        (**self).frame()
+    }
+
+    fn animate(
+        &self,
+        frame: Rect,
+        alpha: f64,
+        duration: Duration,
+        easing: Easing,
+        completion: Box<dyn FnOnce() + Send>,
+    ) {
+        (**self).animate(frame, alpha, duration, easing, completion)
+    }
+
+    fn screen(&self) -> Option<Screen> {
+        (**self).screen()
+    }
+
+    fn screens(&self) -> Vec<Screen> {
+        (**self).screens()
+    }
+
+    fn mouse_location(&self) -> Point {
+        (**self).mouse_location()
+    }
+
+    fn key_window_screen(&self) -> Option<Screen> {
+        (**self).key_window_screen()
+    }
+
+    fn set_style_mask(&self, style_mask: PanelStyleMask) {
+        (**self).set_style_mask(style_mask)
+    }
+
+    fn style_mask(&self) -> PanelStyleMask {
+        (**self).style_mask()
+    }
+
+    fn set_collection_behaviour(&self, behaviour: CollectionBehavior) {
+        (**self).set_collection_behaviour(behaviour)
+    }
+
+    fn set_floating_panel(&self, flag: bool) {
+        (**self).set_floating_panel(flag)
+    }
+
+    fn set_accepts_mouse_moved_events(&self, flag: bool) {
+        (**self).set_accepts_mouse_moved_events(flag)
+    }
+
+    fn set_ignores_mouse_events(&self, flag: bool) {
+        (**self).set_ignores_mouse_events(flag)
+    }
+
+    fn set_hides_on_deactivate(&self, flag: bool) {
+        (**self).set_hides_on_deactivate(flag)
+    }
+
+    fn set_movable_by_window_background(&self, flag: bool) {
+        (**self).set_movable_by_window_background(flag)
+    }
+
+    fn set_delegate(&self, delegate: id) {
+        (**self).set_delegate(delegate)
+    }
+
+    fn released_when_closed(&self, flag: bool) {
+        (**self).released_when_closed(flag)
+    }
+
+    fn close(&self) {
+        (**self).close()
+    }
+}
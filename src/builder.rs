@@ -0,0 +1,327 @@
+use std::marker::PhantomData;
+
+#[cfg(target_os = "macos")]
+use objc::{runtime::Object, Message};
+#[cfg(target_os = "macos")]
+use objc_id::{Id, ShareId};
+use tauri::{Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::{
+    kind::PanelKind, CollectionBehavior, Error, ManagerExt, Panel, PanelLevel, PanelStyleMask,
+    Standard, WebviewWindowExt,
+};
+
+/// Options applied to a panel right after its window has been converted.
+///
+/// Options left as `None` keep whatever the panel's [`PanelKind`] configured.
+#[derive(Default)]
+pub struct WebviewPanelConfig {
+    pub level: Option<PanelLevel>,
+    pub style_mask: Option<PanelStyleMask>,
+    pub collection_behaviour: Option<CollectionBehavior>,
+    pub can_become_key_window: Option<bool>,
+    pub can_become_main_window: Option<bool>,
+    pub floating: Option<bool>,
+    pub hides_on_deactivate: Option<bool>,
+    #[cfg(target_os = "macos")]
+    pub delegate: Option<ShareId<Object>>,
+}
+
+impl WebviewPanelConfig {
+    /// Checks the options without touching any panel.
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some(style_mask) = self.style_mask {
+            style_mask.validate()?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies the options to `panel`, nothing is applied if they are invalid.
+    pub fn apply(&self, panel: &Panel) -> Result<(), Error> {
+        self.validate()?;
+
+        if let Some(level) = &self.level {
+            panel.set_level(level.clone());
+        }
+
+        if let Some(style_mask) = self.style_mask {
+            panel.set_style_mask(style_mask)?;
+        }
+
+        if let Some(behaviour) = self.collection_behaviour {
+            panel.set_collection_behaviour(behaviour);
+        }
+
+        if let Some(flag) = self.can_become_key_window {
+            panel.set_can_become_key_window(flag);
+        }
+
+        if let Some(flag) = self.can_become_main_window {
+            panel.set_can_become_main_window(flag);
+        }
+
+        if let Some(flag) = self.floating {
+            panel.set_floating_panel(flag);
+        }
+
+        if let Some(flag) = self.hides_on_deactivate {
+            panel.set_hides_on_deactivate(flag);
+        }
+
+        #[cfg(target_os = "macos")]
+        if let Some(delegate) = &self.delegate {
+            panel.set_delegate_object(delegate.clone());
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a webview window and converts it to a fully configured panel in one step.
+///
+/// ```no_run
+/// use tauri::WebviewUrl;
+/// use tauri_nspanel::{CollectionBehavior, Overlay, PanelBuilder, PanelLevel};
+///
+/// fn setup(app: &tauri::App) -> tauri::Result<()> {
+///     let panel = PanelBuilder::new(app, "hud", WebviewUrl::default())
+///         .kind::<Overlay>()
+///         .window(|window| window.inner_size(320.0, 80.0).transparent(true))
+///         .level(PanelLevel::Floating)
+///         .collection_behaviour(CollectionBehavior::CAN_JOIN_ALL_SPACES)
+///         .position(100.0, 100.0)
+///         .build()?;
+///
+///     panel.show();
+///     Ok(())
+/// }
+/// ```
+pub struct PanelBuilder<'a, R: Runtime, M: Manager<R>, K: PanelKind = Standard> {
+    window: WebviewWindowBuilder<'a, R, M>,
+    config: WebviewPanelConfig,
+    forward_events: bool,
+    remember_frame: bool,
+    kind: PhantomData<K>,
+}
+
+impl<'a, R: Runtime, M: Manager<R>> PanelBuilder<'a, R, M, Standard> {
+    /// Starts building a [`Standard`] panel, the window is created hidden.
+    pub fn new<L: Into<String>>(manager: &'a M, label: L, url: WebviewUrl) -> Self {
+        Self::from_window_builder(WebviewWindowBuilder::new(manager, label, url).visible(false))
+    }
+
+    /// Starts building a [`Standard`] panel from an existing window builder.
+    pub fn from_window_builder(window: WebviewWindowBuilder<'a, R, M>) -> Self {
+        Self {
+            window,
+            config: WebviewPanelConfig::default(),
+            forward_events: false,
+            remember_frame: false,
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<'a, R: Runtime, M: Manager<R>, K: PanelKind> PanelBuilder<'a, R, M, K> {
+    /// Sets the kind of panel the window is converted to.
+    pub fn kind<T: PanelKind>(self) -> PanelBuilder<'a, R, M, T> {
+        PanelBuilder {
+            window: self.window,
+            config: self.config,
+            forward_events: self.forward_events,
+            remember_frame: self.remember_frame,
+            kind: PhantomData,
+        }
+    }
+
+    /// Customizes the underlying window builder.
+    pub fn window(
+        mut self,
+        f: impl FnOnce(WebviewWindowBuilder<'a, R, M>) -> WebviewWindowBuilder<'a, R, M>,
+    ) -> Self {
+        self.window = f(self.window);
+        self
+    }
+
+    /// Replaces every panel option at once.
+    pub fn config(mut self, config: WebviewPanelConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn level(mut self, level: PanelLevel) -> Self {
+        self.config.level = Some(level);
+        self
+    }
+
+    pub fn style_mask(mut self, style_mask: PanelStyleMask) -> Self {
+        self.config.style_mask = Some(style_mask);
+        self
+    }
+
+    pub fn collection_behaviour(mut self, behaviour: CollectionBehavior) -> Self {
+        self.config.collection_behaviour = Some(behaviour);
+        self
+    }
+
+    pub fn can_become_key_window(mut self, flag: bool) -> Self {
+        self.config.can_become_key_window = Some(flag);
+        self
+    }
+
+    pub fn can_become_main_window(mut self, flag: bool) -> Self {
+        self.config.can_become_main_window = Some(flag);
+        self
+    }
+
+    pub fn floating(mut self, flag: bool) -> Self {
+        self.config.floating = Some(flag);
+        self
+    }
+
+    pub fn hides_on_deactivate(mut self, flag: bool) -> Self {
+        self.config.hides_on_deactivate = Some(flag);
+        self
+    }
+
+    /// Emits the panel's delegate events to its webview, see
+    /// [`ManagerExt::forward_panel_events`].
+    pub fn forward_events(mut self) -> Self {
+        self.forward_events = true;
+        self
+    }
+
+    /// Restores the panel's frame from the last launch and saves it whenever it changes, see
+    /// [`ManagerExt::remember_panel_frame`].
+    pub fn remember_frame(mut self) -> Self {
+        self.remember_frame = true;
+        self
+    }
+
+    /// The initial position of the panel, in logical pixels.
+    pub fn position(mut self, x: f64, y: f64) -> Self {
+        self.window = self.window.position(x, y);
+        self
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn delegate<T: Message>(mut self, delegate: Id<T>) -> Self {
+        self.config.delegate = Some(crate::panel::delegate_object(delegate));
+        self
+    }
+
+    /// Builds the window, converts it and applies the panel options.
+    ///
+    /// Fails without creating the window if the options are invalid, and destroys the window
+    /// if it cannot be turned into the panel.
+    ///
+    /// The returned panel is registered with the plugin, so it can be looked up with
+    /// [`ManagerExt::get_webview_panel`](crate::ManagerExt::get_webview_panel).
+    pub fn build(self) -> tauri::Result<Panel> {
+        self.config.validate()?;
+
+        let window = self.window.build()?;
+
+        Self::finish(
+            &window,
+            self.config,
+            self.forward_events,
+            self.remember_frame,
+        )
+        .map_err(|error| {
+            let _ = window.destroy();
+            error
+        })
+    }
+
+    /// Converts `window` and applies the options, `config` and its delegate are dropped
+    /// before returning.
+    fn finish(
+        window: &WebviewWindow<R>,
+        config: WebviewPanelConfig,
+        forward_events: bool,
+        remember_frame: bool,
+    ) -> tauri::Result<Panel> {
+        let panel = window.to_panel_of::<K>()?;
+
+        config.apply(&panel)?;
+
+        if forward_events {
+            window.forward_panel_events(window.label())?;
+        }
+
+        if remember_frame {
+            window.remember_panel_frame(window.label())?;
+        }
+
+        Ok(panel)
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{self, PanelCall},
+        testing::app,
+        Overlay,
+    };
+
+    #[test]
+    fn builder_returns_a_configured_registered_panel() {
+        let app = app(test_util::init());
+        let panel = PanelBuilder::new(&app, "hud", WebviewUrl::default())
+            .kind::<Overlay>()
+            .level(PanelLevel::Floating)
+            .collection_behaviour(CollectionBehavior::CAN_JOIN_ALL_SPACES)
+            .can_become_key_window(true)
+            .floating(false)
+            .build()
+            .unwrap();
+
+        assert!(panel.is::<Overlay>());
+        assert!(app.get_webview_panel("hud").unwrap().ptr_eq(&panel));
+
+        let fake = test_util::fake_panel(&app, "hud").unwrap();
+        fake.assert_called(&PanelCall::SetLevel(PanelLevel::Floating));
+        assert_eq!(panel.level(), PanelLevel::Floating);
+        fake.assert_collection_behaviour(CollectionBehavior::CAN_JOIN_ALL_SPACES);
+        assert!(!fake.state().floating);
+
+        panel.show();
+        fake.assert_key();
+    }
+
+    #[test]
+    fn builder_rejects_invalid_style_mask_before_creating_the_window() {
+        let app = app(test_util::init());
+        let result = PanelBuilder::new(&app, "hud", WebviewUrl::default())
+            .style_mask(PanelStyleMask::TITLED | PanelStyleMask::FULL_SCREEN)
+            .build();
+
+        assert!(result.is_err());
+        assert!(app.get_webview_window("hud").is_none());
+    }
+
+    #[test]
+    fn builder_fails_when_the_window_cannot_become_the_panel() {
+        use crate::testing::configured_app;
+
+        // The configuration converts "hud" to an overlay as soon as its window is created
+        let app = configured_app(
+            test_util::init(),
+            serde_json::json!({ "panels": { "hud": { "kind": "overlay" } } }),
+        )
+        .unwrap();
+        let result = PanelBuilder::new(&app, "hud", WebviewUrl::default()).build();
+
+        // The mock runtime forgets destroyed windows without telling the app, so only the
+        // error can be checked here
+        let expected = Error::WrongPanelKind {
+            expected: Standard::CLASS_NAME,
+            found: Overlay::CLASS_NAME,
+        };
+        assert_eq!(result.unwrap_err().to_string(), expected.to_string());
+    }
+}
@@ -0,0 +1,37 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// Platform independent mirror of AppKit's
+    /// [`NSWindowCollectionBehavior`](https://developer.apple.com/documentation/appkit/nswindow/collectionbehavior).
+    ///
+    /// The bit values match AppKit, so converting to the native type is lossless.
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct CollectionBehavior: u64 {
+        const CAN_JOIN_ALL_SPACES = 1 << 0;
+        const MOVE_TO_ACTIVE_SPACE = 1 << 1;
+        const MANAGED = 1 << 2;
+        const TRANSIENT = 1 << 3;
+        const STATIONARY = 1 << 4;
+        const PARTICIPATES_IN_CYCLE = 1 << 5;
+        const IGNORES_CYCLE = 1 << 6;
+        const FULL_SCREEN_PRIMARY = 1 << 7;
+        const FULL_SCREEN_AUXILIARY = 1 << 8;
+        const FULL_SCREEN_NONE = 1 << 9;
+        const FULL_SCREEN_ALLOWS_TILING = 1 << 11;
+        const FULL_SCREEN_DISALLOWS_TILING = 1 << 12;
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<CollectionBehavior> for cocoa::appkit::NSWindowCollectionBehavior {
+    fn from(behaviour: CollectionBehavior) -> Self {
+        Self::from_bits_retain(behaviour.bits())
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<cocoa::appkit::NSWindowCollectionBehavior> for CollectionBehavior {
+    fn from(behaviour: cocoa::appkit::NSWindowCollectionBehavior) -> Self {
+        Self::from_bits_retain(behaviour.bits())
+    }
+}
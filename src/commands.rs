@@ -0,0 +1,267 @@
+//! The commands behind the guest JavaScript API, every command takes the label of the panel
+//! it drives.
+//!
+//! Panels are driven through [`Panel::run_on_main_thread`], so a command whose call cannot
+//! reach the main thread fails instead of reporting a made up result.
+//!
+//! Each command is gated by its own permission, whose scope can restrict the panels it may
+//! drive, see `scope.rs`.
+
+use std::sync::Arc;
+
+use tauri::{
+    async_runtime, command,
+    ipc::{CommandScope, GlobalScope},
+    AppHandle, Runtime,
+};
+
+use crate::{scope::PanelScope, Animation, Error, ManagerExt, Panel, PanelLevel, Rect, ShowTarget};
+
+type Scope = CommandScope<PanelScope>;
+type Global = GlobalScope<PanelScope>;
+
+/// Looks up the panel a command drives, if the command's scope allows it.
+fn panel<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+    scope: &Scope,
+    global: &Global,
+) -> Result<Panel, Error> {
+    lookup(
+        app,
+        label,
+        &[scope.allows().as_slice(), global.allows()].concat(),
+        &[scope.denies().as_slice(), global.denies()].concat(),
+    )
+}
+
+fn lookup<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+    allows: &[Arc<PanelScope>],
+    denies: &[Arc<PanelScope>],
+) -> Result<Panel, Error> {
+    crate::scope::check(
+        label,
+        allows.iter().map(|entry| &**entry),
+        denies.iter().map(|entry| &**entry),
+    )?;
+
+    app.get_webview_panel(label)
+}
+
+#[command]
+pub(crate) fn show<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    target: Option<ShowTarget>,
+    scope: Scope,
+    global: Global,
+) -> Result<(), Error> {
+    let panel = panel(&app, &label, &scope, &global)?;
+
+    match target {
+        Some(target) => panel.show_on(target),
+        None => panel.run_on_main_thread(|panel| panel.show()),
+    }
+}
+
+#[command]
+pub(crate) fn hide<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    scope: Scope,
+    global: Global,
+) -> Result<(), Error> {
+    panel(&app, &label, &scope, &global)?.run_on_main_thread(|panel| panel.order_out())
+}
+
+/// Shows the panel if it is hidden and hides it otherwise, returns whether it is now visible.
+#[command]
+pub(crate) fn toggle<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    scope: Scope,
+    global: Global,
+) -> Result<bool, Error> {
+    toggle_visibility(&panel(&app, &label, &scope, &global)?)
+}
+
+fn toggle_visibility(panel: &Panel) -> Result<bool, Error> {
+    panel.run_on_main_thread(|panel| {
+        if panel.is_visible() {
+            panel.order_out();
+            false
+        } else {
+            panel.show();
+            true
+        }
+    })
+}
+
+#[command]
+pub(crate) fn close<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    scope: Scope,
+    global: Global,
+) -> Result<(), Error> {
+    panel(&app, &label, &scope, &global)?.run_on_main_thread(|panel| panel.close())
+}
+
+#[command]
+pub(crate) fn set_level<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    level: PanelLevel,
+    scope: Scope,
+    global: Global,
+) -> Result<(), Error> {
+    panel(&app, &label, &scope, &global)?.run_on_main_thread(move |panel| panel.set_level(level))
+}
+
+#[command]
+pub(crate) fn set_alpha<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    alpha: f64,
+    scope: Scope,
+    global: Global,
+) -> Result<(), Error> {
+    panel(&app, &label, &scope, &global)?
+        .run_on_main_thread(move |panel| panel.set_alpha_value(alpha))
+}
+
+#[command]
+pub(crate) fn set_frame<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    frame: Rect,
+    animate: Option<bool>,
+    scope: Scope,
+    global: Global,
+) -> Result<(), Error> {
+    let animate = animate.unwrap_or_default();
+
+    panel(&app, &label, &scope, &global)?
+        .run_on_main_thread(move |panel| panel.set_frame(frame, animate))
+}
+
+#[command]
+pub(crate) fn is_visible<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    scope: Scope,
+    global: Global,
+) -> Result<bool, Error> {
+    panel(&app, &label, &scope, &global)?.run_on_main_thread(|panel| panel.is_visible())
+}
+
+/// Resolves once the transition is over, to `false` if another one interrupted it.
+#[command]
+pub(crate) async fn show_animated<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    animation: Option<Animation>,
+    scope: Scope,
+    global: Global,
+) -> Result<bool, Error> {
+    let panel = panel(&app, &label, &scope, &global)?;
+    let (tx, mut rx) = async_runtime::channel(1);
+
+    panel.show_animated(animation.unwrap_or_default(), move |finished| {
+        let _ = tx.try_send(finished);
+    })?;
+
+    Ok(rx.recv().await.unwrap_or_default())
+}
+
+/// Resolves once the transition is over, to `false` if another one interrupted it.
+#[command]
+pub(crate) async fn hide_animated<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    animation: Option<Animation>,
+    scope: Scope,
+    global: Global,
+) -> Result<bool, Error> {
+    let panel = panel(&app, &label, &scope, &global)?;
+    let (tx, mut rx) = async_runtime::channel(1);
+
+    panel.hide_animated(animation.unwrap_or_default(), move |finished| {
+        let _ = tx.try_send(finished);
+    })?;
+
+    Ok(rx.recv().await.unwrap_or_default())
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use tauri::{test::MockRuntime, App, Manager};
+
+    use super::*;
+    use crate::{
+        test_util,
+        testing::{app, scope, window},
+        WebviewWindowExt,
+    };
+
+    /// An app with the panels `main` and `settings`.
+    fn panels() -> App<MockRuntime> {
+        let app = app(test_util::init());
+
+        for label in ["main", "settings"] {
+            window(&app, label).to_panel().unwrap();
+        }
+
+        app
+    }
+
+    #[test]
+    fn toggle_flips_the_visibility() {
+        let app = panels();
+        let panel = app.get_webview_panel("main").unwrap();
+        let fake = test_util::fake_panel(&app, "main").unwrap();
+        panel.order_out();
+
+        assert!(toggle_visibility(&panel).unwrap());
+        fake.assert_visible();
+
+        assert!(!toggle_visibility(&panel).unwrap());
+        fake.assert_hidden();
+    }
+
+    #[test]
+    fn scopes_restrict_the_panels() {
+        let app = panels();
+        let handle = app.app_handle();
+        let allows = [Arc::new(scope("settings"))];
+
+        assert!(lookup(handle, "settings", &allows, &[])
+            .unwrap()
+            .ptr_eq(&app.get_webview_panel("settings").unwrap()));
+        assert_eq!(
+            lookup(handle, "main", &allows, &[]).unwrap_err(),
+            Error::PanelNotAllowed
+        );
+        assert_eq!(
+            lookup(handle, "main", &[], &[Arc::new(scope("*"))]).unwrap_err(),
+            Error::PanelNotAllowed
+        );
+        assert!(lookup(handle, "main", &[], &[]).is_ok());
+    }
+
+    #[test]
+    fn unknown_labels_are_reported() {
+        let app = panels();
+
+        assert_eq!(
+            lookup(app.app_handle(), "missing", &[], &[]).unwrap_err(),
+            Error::PanelNotFound
+        );
+        assert_eq!(
+            serde_json::to_value(Error::PanelNotFound).unwrap(),
+            serde_json::json!({ "kind": "PanelNotFound", "message": "panel not found" })
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+use bitflags::Flags;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use tauri::{Runtime, WebviewWindow};
+
+use crate::{
+    CollectionBehavior, Error, ManagerExt, Panel, PanelLevel, PanelStyleMask, WebviewPanelConfig,
+    WebviewWindowExt,
+};
+
+/// The plugin's configuration, `plugins.nspanel` in `tauri.conf.json`.
+///
+/// ```json
+/// {
+///   "plugins": {
+///     "nspanel": {
+///       "panels": {
+///         "hud": {
+///           "kind": "overlay",
+///           "level": "floating",
+///           "styleMask": ["nonactivatingPanel"],
+///           "collectionBehaviour": ["canJoinAllSpaces", "fullScreenAuxiliary"],
+///           "canBecomeKeyWindow": false,
+///           "hidesOnDeactivate": false,
+///           "rememberFrame": true
+///         }
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// Windows listed under `panels` are converted and configured as soon as their webview is
+/// ready. Flags are given by their name in camel case.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Config {
+    /// Panels to create, by window label.
+    #[serde(default)]
+    pub panels: HashMap<String, PanelConfig>,
+}
+
+/// How a window declared in [`Config`] is turned into a panel.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PanelConfig {
+    #[serde(default)]
+    pub kind: ConfiguredKind,
+    pub level: Option<PanelLevel>,
+    #[serde(default, deserialize_with = "flags")]
+    pub style_mask: Option<PanelStyleMask>,
+    #[serde(default, deserialize_with = "flags")]
+    pub collection_behaviour: Option<CollectionBehavior>,
+    pub can_become_key_window: Option<bool>,
+    pub can_become_main_window: Option<bool>,
+    pub hides_on_deactivate: Option<bool>,
+    /// Restores the panel's frame from the last launch, see
+    /// [`ManagerExt::remember_panel_frame`](crate::ManagerExt::remember_panel_frame).
+    #[serde(default)]
+    pub remember_frame: bool,
+}
+
+/// The built-in [`PanelKind`](crate::PanelKind) a configured window is converted to.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfiguredKind {
+    /// [`Standard`](crate::Standard)
+    #[default]
+    Panel,
+    /// [`Overlay`](crate::Overlay)
+    Overlay,
+}
+
+impl Config {
+    /// Checks every panel's options, naming the first invalid panel.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut labels: Vec<_> = self.panels.keys().collect();
+        labels.sort();
+
+        for label in labels {
+            self.panels[label]
+                .options()
+                .validate()
+                .map_err(|e: Error| format!("invalid `{label}` panel: {e}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PanelConfig {
+    /// The options applied to the panel once converted.
+    pub fn options(&self) -> WebviewPanelConfig {
+        WebviewPanelConfig {
+            level: self.level.clone(),
+            style_mask: self.style_mask,
+            collection_behaviour: self.collection_behaviour,
+            can_become_key_window: self.can_become_key_window,
+            can_become_main_window: self.can_become_main_window,
+            hides_on_deactivate: self.hides_on_deactivate,
+            ..Default::default()
+        }
+    }
+
+    /// Converts `window` to the configured kind and applies the options.
+    pub(crate) fn convert<R: Runtime>(&self, window: &WebviewWindow<R>) -> Result<Panel, Error> {
+        let panel = match self.kind {
+            ConfiguredKind::Panel => window.to_panel()?,
+            ConfiguredKind::Overlay => window.to_overlay_panel()?,
+        };
+
+        self.options().apply(&panel)?;
+
+        if self.remember_frame {
+            window.remember_panel_frame(window.label())?;
+        }
+
+        Ok(panel)
+    }
+}
+
+/// Deserializes bitflags from a list of flag names in camel case.
+fn flags<'de, D, F>(deserializer: D) -> Result<Option<F>, D::Error>
+where
+    D: Deserializer<'de>,
+    F: Flags,
+{
+    struct FlagsVisitor<F>(PhantomData<F>);
+
+    impl<'de, F: Flags> Visitor<'de> for FlagsVisitor<F> {
+        type Value = F;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a list of flag names")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<F, A::Error> {
+            let mut flags = F::empty();
+
+            while let Some(name) = seq.next_element::<String>()? {
+                let flag = F::from_name(&screaming_snake_case(&name)).ok_or_else(|| {
+                    let expected: Vec<_> = F::FLAGS
+                        .iter()
+                        .map(|flag| format!("`{}`", camel_case(flag.name())))
+                        .collect();
+
+                    de::Error::custom(format!(
+                        "unknown flag `{name}`, expected one of {}",
+                        expected.join(", ")
+                    ))
+                })?;
+
+                flags.insert(flag);
+            }
+
+            Ok(flags)
+        }
+    }
+
+    deserializer
+        .deserialize_seq(FlagsVisitor(PhantomData))
+        .map(Some)
+}
+
+/// `fullScreenAuxiliary` to `FULL_SCREEN_AUXILIARY`
+fn screaming_snake_case(name: &str) -> String {
+    let mut converted = String::with_capacity(name.len() + 4);
+
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            converted.push('_');
+        }
+        converted.push(c.to_ascii_uppercase());
+    }
+
+    converted
+}
+
+/// `FULL_SCREEN_AUXILIARY` to `fullScreenAuxiliary`
+fn camel_case(name: &str) -> String {
+    let mut words = name.split('_').map(str::to_ascii_lowercase);
+    let mut converted = words.next().unwrap_or_default();
+
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            converted.push(first.to_ascii_uppercase());
+            converted.push_str(chars.as_str());
+        }
+    }
+
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn config(value: serde_json::Value) -> Result<Config, String> {
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn panels_deserialize_with_their_options() {
+        let config = config(json!({
+            "panels": {
+                "hud": {
+                    "kind": "overlay",
+                    "level": "floating",
+                    "styleMask": ["nonactivatingPanel", "hudWindow", "utilityWindow"],
+                    "collectionBehaviour": ["canJoinAllSpaces", "fullScreenAuxiliary"],
+                    "canBecomeKeyWindow": false,
+                    "hidesOnDeactivate": false
+                },
+                "main": {}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            config.panels["hud"],
+            PanelConfig {
+                kind: ConfiguredKind::Overlay,
+                level: Some(PanelLevel::Floating),
+                style_mask: Some(
+                    PanelStyleMask::NONACTIVATING_PANEL
+                        | PanelStyleMask::HUD_WINDOW
+                        | PanelStyleMask::UTILITY_WINDOW
+                ),
+                collection_behaviour: Some(
+                    CollectionBehavior::CAN_JOIN_ALL_SPACES
+                        | CollectionBehavior::FULL_SCREEN_AUXILIARY
+                ),
+                can_become_key_window: Some(false),
+                can_become_main_window: None,
+                hides_on_deactivate: Some(false),
+                remember_frame: false,
+            }
+        );
+        assert_eq!(config.panels["main"], PanelConfig::default());
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn an_empty_list_is_borderless() {
+        let config = config(json!({ "panels": { "main": { "styleMask": [] } } })).unwrap();
+
+        assert_eq!(
+            config.panels["main"].style_mask,
+            Some(PanelStyleMask::BORDERLESS)
+        );
+    }
+
+    #[test]
+    fn mistakes_are_explained() {
+        let error = config(json!({ "panels": { "main": { "styleMask": ["nonActivating"] } } }))
+            .unwrap_err();
+        assert!(
+            error.starts_with("unknown flag `nonActivating`, expected one of `titled`, `closable`"),
+            "{error}"
+        );
+        assert!(error.contains("`nonactivatingPanel`"), "{error}");
+
+        let error = config(json!({ "panels": { "main": { "kind": "sheet" } } })).unwrap_err();
+        assert!(
+            error.starts_with("unknown variant `sheet`, expected `panel` or `overlay`"),
+            "{error}"
+        );
+
+        let error =
+            config(json!({ "panels": { "main": { "hidesOnDeactivation": true } } })).unwrap_err();
+        assert!(
+            error.starts_with("unknown field `hidesOnDeactivation`"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn invalid_style_masks_name_the_panel() {
+        let config =
+            config(json!({ "panels": { "hud": { "styleMask": ["hudWindow"] } } })).unwrap();
+
+        assert_eq!(
+            config.validate(),
+            Err("invalid `hud` panel: invalid style mask: a HUD panel must also be a utility window".into())
+        );
+    }
+
+    #[test]
+    fn flag_names_round_trip() {
+        let names = PanelStyleMask::FLAGS
+            .iter()
+            .map(|flag| flag.name())
+            .chain(CollectionBehavior::FLAGS.iter().map(|flag| flag.name()));
+
+        for name in names {
+            assert_eq!(screaming_snake_case(&camel_case(name)), name);
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn configured_windows_are_converted() {
+        use crate::{
+            test_util,
+            testing::{configured_app, window},
+            Error, ManagerExt, Overlay,
+        };
+
+        let app = configured_app(
+            test_util::init(),
+            serde_json::json!({
+                "panels": {
+                    "hud": {
+                        "kind": "overlay",
+                        "level": "floating",
+                        "collectionBehaviour": ["canJoinAllSpaces"],
+                        "hidesOnDeactivate": false
+                    }
+                }
+            }),
+        )
+        .unwrap();
+        window(&app, "hud");
+        window(&app, "main");
+
+        assert!(app.get_webview_panel_of::<Overlay>("hud").is_ok());
+        assert_eq!(
+            app.get_webview_panel("main").unwrap_err(),
+            Error::PanelNotFound
+        );
+
+        let fake = test_util::fake_panel(&app, "hud").unwrap();
+        fake.assert_level(PanelLevel::Floating);
+        fake.assert_collection_behaviour(CollectionBehavior::CAN_JOIN_ALL_SPACES);
+        assert!(!fake.state().hides_on_deactivate);
+    }
+
+    #[test]
+    fn invalid_configurations_fail_the_setup() {
+        use crate::testing::configured_app;
+
+        let error = configured_app(
+            crate::init(),
+            serde_json::json!({ "panels": { "hud": { "styleMask": ["hudWindow"] } } }),
+        )
+        .err()
+        .unwrap();
+
+        assert!(
+            error.to_string().contains(
+                "invalid `plugins.nspanel` configuration: invalid `hud` panel: invalid style mask"
+            ),
+            "{error}"
+        );
+
+        let error = configured_app(
+            crate::init(),
+            serde_json::json!({ "panels": { "hud": { "kind": "sheet" } } }),
+        )
+        .err()
+        .unwrap();
+
+        assert!(
+            error.to_string().contains("unknown variant `sheet`"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Conversions between Tauri's window coordinates and AppKit's.
+//!
+//! Tauri places a window by its top left corner, measured from the top left of the primary
+//! screen in logical or physical pixels. AppKit places it by its bottom left corner, measured
+//! from the bottom left of the primary screen in points. The primary screen is the one holding
+//! the menu bar, the first of [`PanelBackend::screens`](crate::PanelBackend::screens).
+
+use tauri::{LogicalPosition, Position};
+
+use crate::{Point, Rect, Size};
+
+/// The AppKit origin of a frame of `size` whose top left corner is at `position`.
+///
+/// `scale_factor` converts physical positions to points.
+pub fn to_cocoa_origin(
+    position: Position,
+    size: Size,
+    primary_height: f64,
+    scale_factor: f64,
+) -> Point {
+    let position: LogicalPosition<f64> = position.to_logical(scale_factor);
+    Point::new(position.x, primary_height - position.y - size.height)
+}
+
+/// The Tauri position, in logical pixels, of the top left corner of `frame`.
+pub fn to_tauri_position(frame: Rect, primary_height: f64) -> LogicalPosition<f64> {
+    LogicalPosition::new(
+        frame.origin.x,
+        primary_height - frame.origin.y - frame.size.height,
+    )
+}
+
+/// `size` in points, `scale_factor` converts physical sizes.
+pub fn to_cocoa_size(size: tauri::Size, scale_factor: f64) -> Size {
+    let size: tauri::LogicalSize<f64> = size.to_logical(scale_factor);
+    Size::new(size.width, size.height)
+}
+
+/// `frame` resized to `size` with its top left corner in place, the way Tauri resizes windows.
+pub fn resize_from_top_left(frame: Rect, size: Size) -> Rect {
+    Rect::new(
+        frame.origin.x,
+        frame.max_y() - size.height,
+        size.width,
+        size.height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tauri::{LogicalSize, PhysicalPosition, PhysicalSize};
+
+    use super::*;
+
+    const PRIMARY_HEIGHT: f64 = 900.0;
+
+    #[test]
+    fn logical_positions_are_flipped() {
+        let origin = to_cocoa_origin(
+            LogicalPosition::new(100.0, 50.0).into(),
+            Size::new(320.0, 80.0),
+            PRIMARY_HEIGHT,
+            2.0,
+        );
+
+        // 50 points below the top of the screen, so the bottom is 130 points below it
+        assert_eq!(origin, Point::new(100.0, 770.0));
+    }
+
+    #[test]
+    fn physical_positions_are_scaled() {
+        let origin = to_cocoa_origin(
+            PhysicalPosition::new(200, 100).into(),
+            Size::new(320.0, 80.0),
+            PRIMARY_HEIGHT,
+            2.0,
+        );
+
+        assert_eq!(origin, Point::new(100.0, 770.0));
+    }
+
+    #[test]
+    fn positions_above_or_left_of_the_primary_screen_are_kept() {
+        let origin = to_cocoa_origin(
+            LogicalPosition::new(-1440.0, -200.0).into(),
+            Size::new(100.0, 100.0),
+            PRIMARY_HEIGHT,
+            1.0,
+        );
+
+        assert_eq!(origin, Point::new(-1440.0, 1000.0));
+    }
+
+    #[test]
+    fn frames_convert_back_to_the_same_position() {
+        let size = Size::new(320.0, 80.0);
+        let position = LogicalPosition::new(100.0, 50.0);
+        let origin = to_cocoa_origin(position.into(), size, PRIMARY_HEIGHT, 2.0);
+
+        assert_eq!(
+            to_tauri_position(Rect { origin, size }, PRIMARY_HEIGHT),
+            position
+        );
+    }
+
+    #[test]
+    fn sizes_are_scaled() {
+        assert_eq!(
+            to_cocoa_size(LogicalSize::new(320.0, 80.0).into(), 2.0),
+            Size::new(320.0, 80.0)
+        );
+        assert_eq!(
+            to_cocoa_size(PhysicalSize::new(640, 160).into(), 2.0),
+            Size::new(320.0, 80.0)
+        );
+    }
+
+    #[test]
+    fn resizing_keeps_the_top_left_corner() {
+        let frame = Rect::new(100.0, 770.0, 320.0, 80.0);
+        let resized = resize_from_top_left(frame, Size::new(400.0, 200.0));
+
+        assert_eq!(resized, Rect::new(100.0, 650.0, 400.0, 200.0));
+        assert_eq!(
+            to_tauri_position(resized, PRIMARY_HEIGHT),
+            to_tauri_position(frame, PRIMARY_HEIGHT)
+        );
+    }
+}
@@ -0,0 +1,586 @@
+//! Runtime support for [`panel_delegate!`](crate::panel_delegate), not meant to be used directly.
+
+use std::{ffi::c_void, rc::Rc};
+
+#[cfg(target_os = "macos")]
+use cocoa::{
+    base::{id, nil, BOOL, NO, YES},
+    foundation::{NSRect, NSSize},
+};
+#[cfg(target_os = "macos")]
+use objc::{
+    msg_send,
+    runtime::{Object, Sel},
+    sel, sel_impl, Message, MessageArguments,
+};
+
+#[cfg(target_os = "macos")]
+use crate::event::{NativeObject, Reply, Subscribers};
+use crate::PanelEvent;
+
+/// The closure a delegate hands its events to.
+pub type Listener = Box<dyn Fn(PanelEvent)>;
+
+/// The Objective-C selector of a delegate method given in snake case.
+///
+/// Each part of the selector is camel cased and followed by a colon, parts are separated by a
+/// double underscore: `window_did_move` becomes `windowDidMove:` and
+/// `window__will_position_sheet__using_rect` becomes `window:willPositionSheet:usingRect:`.
+pub fn selector(method: &str) -> String {
+    let mut selector = String::with_capacity(method.len() + 1);
+
+    for part in method.split("__") {
+        let mut words = part.split('_').filter(|word| !word.is_empty());
+
+        if let Some(first) = words.next() {
+            selector.push_str(first);
+        }
+
+        for word in words {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                selector.push(first.to_ascii_uppercase());
+                selector.push_str(chars.as_str());
+            }
+        }
+
+        selector.push(':');
+    }
+
+    selector
+}
+
+/// The snake case name of the delegate method with the Objective-C `selector`, the inverse of
+/// [`selector`]: `window:willPositionSheet:usingRect:` becomes
+/// `window__will_position_sheet__using_rect`.
+pub fn method(selector: &str) -> String {
+    let parts: Vec<String> = selector
+        .split(':')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut method = String::with_capacity(part.len() + 4);
+
+            for c in part.chars() {
+                if c.is_ascii_uppercase() {
+                    method.push('_');
+                    method.push(c.to_ascii_lowercase());
+                } else {
+                    method.push(c);
+                }
+            }
+
+            method
+        })
+        .collect();
+
+    parts.join("__")
+}
+
+/// Moves `listener` to the heap, the returned pointer owns it until it is given to [`replace`].
+pub fn into_raw(listener: Listener) -> *mut c_void {
+    Rc::into_raw(Rc::new(listener)) as *mut c_void
+}
+
+/// Stores `listener` in `slot` and drops the listener it held, if any.
+///
+/// A listener replaced while it runs is only dropped once it returns.
+///
+/// # Safety
+///
+/// `slot` and `listener` must each be null or come from [`into_raw`], and `listener` must not
+/// be stored anywhere else.
+pub unsafe fn replace(slot: &mut *mut c_void, listener: *mut c_void) {
+    let previous = std::mem::replace(slot, listener);
+
+    if !previous.is_null() {
+        drop(Rc::from_raw(previous as *const Listener));
+    }
+}
+
+/// Calls the listener in `slot`, if any, and returns whether there was one.
+///
+/// # Safety
+///
+/// `slot` must be null or come from [`into_raw`].
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+unsafe fn call(slot: *mut c_void, event: impl FnOnce() -> PanelEvent) -> bool {
+    if slot.is_null() {
+        return false;
+    }
+
+    // Keep the listener alive even if it replaces itself
+    let slot = slot as *const Listener;
+    Rc::increment_strong_count(slot);
+    let listener = Rc::from_raw(slot);
+    listener(event());
+
+    true
+}
+
+/// `setListener:`, takes ownership of a listener created by [`into_raw`].
+#[cfg(target_os = "macos")]
+pub extern "C" fn set_listener(this: &mut Object, _: Sel, listener: *mut c_void) {
+    unsafe { replace(this.get_mut_ivar("_listener"), listener) };
+}
+
+/// `setSubscribers:`, takes ownership of a boxed [`Subscribers`].
+#[cfg(target_os = "macos")]
+pub extern "C" fn set_subscribers(this: &mut Object, _: Sel, subscribers: *mut c_void) {
+    let slot: &mut *mut c_void = unsafe { this.get_mut_ivar("_subscribers") };
+    let previous = std::mem::replace(slot, subscribers);
+
+    if !previous.is_null() {
+        drop(unsafe { Box::from_raw(previous as *mut Subscribers) });
+    }
+}
+
+/// Gives `delegate` the subscribers of its panel and returns `true` if it is a
+/// [`panel_delegate!`](crate::panel_delegate) delegate.
+///
+/// Must be called on the main thread.
+#[cfg(target_os = "macos")]
+pub(crate) fn share_subscribers(delegate: id, subscribers: Subscribers) -> bool {
+    let responds: BOOL = unsafe { msg_send![delegate, respondsToSelector: sel!(setSubscribers:)] };
+    if responds == NO {
+        return false;
+    }
+
+    let subscribers = Box::into_raw(Box::new(subscribers)) as *mut c_void;
+    let _: () = unsafe { msg_send![delegate, setSubscribers: subscribers] };
+
+    true
+}
+
+/// A delegate handing every notification with a [`PanelEvent`] variant to the panel's
+/// subscribers, for panels that have no delegate of their own.
+#[cfg(target_os = "macos")]
+pub(crate) fn event_delegate() -> objc_id::ShareId<Object> {
+    crate::panel::delegate_object(crate::panel_delegate!(TauriNSPanelEventDelegate {
+        window_did_become_key,
+        window_did_resign_key,
+        window_did_become_main,
+        window_did_resign_main,
+        window_will_move,
+        window_did_move,
+        window_will_start_live_resize,
+        window_did_resize,
+        window_did_end_live_resize,
+        window_did_change_screen,
+        window_did_change_backing_properties,
+        window_did_change_occlusion_state,
+        window_did_miniaturize,
+        window_did_deminiaturize,
+        window_will_enter_full_screen,
+        window_did_enter_full_screen,
+        window_will_exit_full_screen,
+        window_did_exit_full_screen,
+        window_will_close,
+    }))
+}
+
+/// `setOriginalDelegate:`, retains the delegate the window had before this one.
+#[cfg(target_os = "macos")]
+pub extern "C" fn set_original_delegate(this: &mut Object, _: Sel, original: id) {
+    if original != nil {
+        let _: id = unsafe { msg_send![original, retain] };
+    }
+
+    let slot: &mut id = unsafe { this.get_mut_ivar("_originalDelegate") };
+    let previous = std::mem::replace(slot, original);
+
+    if previous != nil {
+        let _: () = unsafe { msg_send![previous, release] };
+    }
+}
+
+/// `originalDelegate`
+#[cfg(target_os = "macos")]
+pub extern "C" fn original_delegate(this: &Object, _: Sel) -> id {
+    unsafe { *this.get_ivar("_originalDelegate") }
+}
+
+/// `respondsToSelector:`, also answers for the selectors of the original delegate so the
+/// window keeps sending them.
+#[cfg(target_os = "macos")]
+pub extern "C" fn responds_to_selector(this: &Object, _: Sel, selector: Sel) -> BOOL {
+    let implemented: BOOL =
+        unsafe { msg_send![this.class(), instancesRespondToSelector: selector] };
+
+    if implemented == YES || original_for(this, selector).is_some() {
+        YES
+    } else {
+        NO
+    }
+}
+
+/// `forwardingTargetForSelector:`, hands the selectors the delegate does not implement to
+/// the original delegate.
+#[cfg(target_os = "macos")]
+pub extern "C" fn forwarding_target_for_selector(this: &Object, _: Sel, selector: Sel) -> id {
+    original_for(this, selector).map_or(nil, |original| original as *const Object as id)
+}
+
+/// The original delegate, if it implements `selector`.
+#[cfg(target_os = "macos")]
+fn original_for(this: &Object, selector: Sel) -> Option<&Object> {
+    let original: id = unsafe { *this.get_ivar("_originalDelegate") };
+    if original == nil {
+        return None;
+    }
+
+    let responds: BOOL = unsafe { msg_send![original, respondsToSelector: selector] };
+    if responds == YES {
+        Some(unsafe { &*original })
+    } else {
+        None
+    }
+}
+
+/// Sends `selector` to the original delegate, if it implements it.
+#[cfg(target_os = "macos")]
+fn call_original<A, R>(this: &Object, selector: Sel, args: A) -> Option<R>
+where
+    A: MessageArguments,
+    R: std::any::Any,
+{
+    let original = original_for(this, selector)?;
+    unsafe { original.send_message(selector, args) }.ok()
+}
+
+/// Gives `delegate` the window's current delegate, usually tao's, so the window's messages
+/// keep reaching it and Tauri's window events keep working.
+///
+/// Must be called on the main thread, before `delegate` is set on the window.
+#[cfg(target_os = "macos")]
+pub(crate) fn keep_original(window: id, delegate: id) {
+    let responds: BOOL =
+        unsafe { msg_send![delegate, respondsToSelector: sel!(setOriginalDelegate:)] };
+    if responds == NO {
+        return;
+    }
+
+    let mut original: id = unsafe { msg_send![window, delegate] };
+
+    // Replacing a panel delegate, keep the delegate it was forwarding to
+    if original != nil {
+        let wraps: BOOL =
+            unsafe { msg_send![original, respondsToSelector: sel!(originalDelegate)] };
+        if wraps == YES {
+            original = unsafe { msg_send![original, originalDelegate] };
+        }
+    }
+
+    if original == delegate {
+        return;
+    }
+
+    let _: () = unsafe { msg_send![delegate, setOriginalDelegate: original] };
+}
+
+/// Frees what the delegate owns, called from its `dealloc`.
+#[cfg(target_os = "macos")]
+pub fn release(this: &mut Object) {
+    unsafe { replace(this.get_mut_ivar("_listener"), std::ptr::null_mut()) };
+    set_subscribers(this, sel!(setSubscribers:), std::ptr::null_mut());
+    set_original_delegate(this, sel!(setOriginalDelegate:), nil);
+}
+
+/// Hands `event` to the delegate's listener and then to the panel's subscribers.
+#[cfg(target_os = "macos")]
+fn dispatch(this: &Object, event: impl FnOnce() -> PanelEvent) {
+    let listener: *mut c_void = unsafe { *this.get_ivar("_listener") };
+    let subscribers: *mut c_void = unsafe { *this.get_ivar("_subscribers") };
+    let subscribers = unsafe { (subscribers as *const Subscribers).as_ref() };
+
+    if listener.is_null() && subscribers.map_or(true, Subscribers::is_empty) {
+        return;
+    }
+
+    let event = event();
+    unsafe { call(listener, || event.clone()) };
+
+    if let Some(subscribers) = subscribers {
+        subscribers.emit(&event);
+    }
+}
+
+/// Handles a delegate method that receives an `NSNotification` and returns nothing, the event
+/// is the one of its selector.
+///
+/// The original delegate is notified first.
+#[cfg(target_os = "macos")]
+pub extern "C" fn notify(this: &Object, selector: Sel, notification: id) {
+    let _: Option<()> = call_original(this, selector, (notification,));
+    dispatch(this, || {
+        PanelEvent::from_notification(selector.name(), notification)
+    });
+}
+
+/// `windowShouldClose:`
+#[cfg(target_os = "macos")]
+pub extern "C" fn window_should_close(this: &Object, selector: Sel, sender: id) -> BOOL {
+    let default = call_original(this, selector, (sender,)).map_or(true, |flag: BOOL| flag == YES);
+    let reply = Reply::new(default);
+    dispatch(this, || PanelEvent::ShouldClose {
+        reply: reply.clone(),
+    });
+
+    if reply.get() {
+        YES
+    } else {
+        NO
+    }
+}
+
+/// `windowWillResize:toSize:`
+#[cfg(target_os = "macos")]
+pub extern "C" fn window_will_resize_to_size(
+    this: &Object,
+    selector: Sel,
+    sender: id,
+    size: NSSize,
+) -> NSSize {
+    let default: NSSize = call_original(this, selector, (sender, size)).unwrap_or(size);
+    let reply = Reply::new(default.into());
+    dispatch(this, || PanelEvent::WillResize {
+        size: size.into(),
+        reply: reply.clone(),
+    });
+
+    reply.get().into()
+}
+
+/// `windowWillUseStandardFrame:defaultFrame:`
+#[cfg(target_os = "macos")]
+pub extern "C" fn window_will_use_standard_frame_default_frame(
+    this: &Object,
+    selector: Sel,
+    window: id,
+    default_frame: NSRect,
+) -> NSRect {
+    let default: NSRect =
+        call_original(this, selector, (window, default_frame)).unwrap_or(default_frame);
+    let reply = Reply::new(default.into());
+    dispatch(this, || PanelEvent::WillUseStandardFrame {
+        default_frame: default_frame.into(),
+        reply: reply.clone(),
+    });
+
+    reply.get().into()
+}
+
+/// `window:willPositionSheet:usingRect:`
+#[cfg(target_os = "macos")]
+pub extern "C" fn window_will_position_sheet_using_rect(
+    this: &Object,
+    selector: Sel,
+    window: id,
+    sheet: id,
+    rect: NSRect,
+) -> NSRect {
+    let default: NSRect = call_original(this, selector, (window, sheet, rect)).unwrap_or(rect);
+    let reply = Reply::new(default.into());
+    dispatch(this, || PanelEvent::WillPositionSheet {
+        sheet: NativeObject(sheet as _),
+        rect: rect.into(),
+        reply: reply.clone(),
+    });
+
+    reply.get().into()
+}
+
+/// `windowWillReturnFieldEditor:toObject:`
+#[cfg(target_os = "macos")]
+pub extern "C" fn window_will_return_field_editor_to_object(
+    this: &Object,
+    selector: Sel,
+    sender: id,
+    client: id,
+) -> id {
+    let default = call_original(this, selector, (sender, client))
+        .filter(|editor: &id| *editor != nil)
+        .map(|editor| NativeObject(editor as _));
+    let reply = Reply::new(default);
+    dispatch(this, || PanelEvent::WillReturnFieldEditor {
+        client: NativeObject(client as _),
+        reply: reply.clone(),
+    });
+
+    reply
+        .get()
+        .map_or(nil, |editor: NativeObject| editor.0 as id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    /// Counts how often it is dropped.
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    fn counted_listener(drops: &Rc<Cell<usize>>) -> *mut c_void {
+        let counter = DropCounter(drops.clone());
+        into_raw(Box::new(move |_| {
+            let _ = &counter;
+        }))
+    }
+
+    #[test]
+    fn single_part_methods_take_one_argument() {
+        assert_eq!(selector("window_did_move"), "windowDidMove:");
+        assert_eq!(selector("window_did_become_key"), "windowDidBecomeKey:");
+        assert_eq!(
+            selector("window_did_change_backing_properties"),
+            "windowDidChangeBackingProperties:"
+        );
+    }
+
+    #[test]
+    fn double_underscores_split_the_arguments() {
+        assert_eq!(
+            selector("window_will_resize__to_size"),
+            "windowWillResize:toSize:"
+        );
+        assert_eq!(
+            selector("window__will_position_sheet__using_rect"),
+            "window:willPositionSheet:usingRect:"
+        );
+        assert_eq!(
+            selector("window_will_return_field_editor__to_object"),
+            "windowWillReturnFieldEditor:toObject:"
+        );
+    }
+
+    #[test]
+    fn listeners_receive_events() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let received = events.clone();
+        let mut slot = std::ptr::null_mut();
+
+        unsafe {
+            assert!(!call(slot, || PanelEvent::WillClose));
+
+            replace(
+                &mut slot,
+                into_raw(Box::new(move |event| received.borrow_mut().push(event))),
+            );
+            assert!(call(slot, || PanelEvent::DidBecomeKey));
+            assert!(call(slot, || PanelEvent::DidResignKey));
+
+            replace(&mut slot, std::ptr::null_mut());
+        }
+
+        assert_eq!(
+            *events.borrow(),
+            [PanelEvent::DidBecomeKey, PanelEvent::DidResignKey]
+        );
+    }
+
+    #[test]
+    fn listeners_are_dropped_exactly_once() {
+        let drops = Rc::new(Cell::new(0));
+        let mut slot = counted_listener(&drops);
+
+        unsafe {
+            call(slot, || PanelEvent::WillClose);
+            assert_eq!(drops.get(), 0);
+
+            replace(&mut slot, std::ptr::null_mut());
+            assert_eq!(drops.get(), 1);
+
+            replace(&mut slot, std::ptr::null_mut());
+        }
+
+        assert_eq!(drops.get(), 1);
+        assert!(slot.is_null());
+    }
+
+    #[test]
+    fn replaced_listeners_are_dropped() {
+        let first = Rc::new(Cell::new(0));
+        let second = Rc::new(Cell::new(0));
+        let mut slot = counted_listener(&first);
+
+        unsafe {
+            replace(&mut slot, counted_listener(&second));
+            assert_eq!((first.get(), second.get()), (1, 0));
+
+            replace(&mut slot, std::ptr::null_mut());
+        }
+
+        assert_eq!((first.get(), second.get()), (1, 1));
+    }
+
+    #[test]
+    fn listeners_outlive_their_own_replacement() {
+        let drops = Rc::new(Cell::new(0));
+        let slot = Rc::new(Cell::new(std::ptr::null_mut()));
+
+        let counter = DropCounter(drops.clone());
+        let own_slot = slot.clone();
+        let listener = into_raw(Box::new(move |_| {
+            let mut current = own_slot.get();
+            unsafe { replace(&mut current, std::ptr::null_mut()) };
+            own_slot.set(current);
+
+            // Still alive while running
+            assert_eq!(counter.0.get(), 0);
+        }));
+        slot.set(listener);
+
+        unsafe { call(slot.get(), || PanelEvent::WillClose) };
+
+        assert_eq!(drops.get(), 1);
+        assert!(slot.get().is_null());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn delegates_are_released_with_their_window() {
+        use objc::class;
+
+        let drops = Rc::new(Cell::new(0));
+        let delegate = crate::panel_delegate!(ReleasedDelegate { window_did_move });
+        let counter = DropCounter(drops.clone());
+        delegate.set_listener(Box::new(move |_| {
+            let _ = &counter;
+        }));
+
+        // Any object owns the delegate the way a panel does
+        let window: id = unsafe { msg_send![class!(NSObject), new] };
+        crate::raw_panel::retain_delegate(window, &*delegate as *const _ as id);
+        drop(delegate);
+        assert_eq!(drops.get(), 0);
+
+        let _: () = unsafe { msg_send![window, release] };
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn selectors_name_their_method() {
+        for method in [
+            "window_did_move",
+            "window_did_change_backing_properties",
+            "window_will_resize__to_size",
+            "window__will_position_sheet__using_rect",
+            "window_did_expose2",
+        ] {
+            assert_eq!(super::method(&selector(method)), method);
+        }
+    }
+
+    #[test]
+    fn stray_underscores_are_ignored() {
+        assert_eq!(selector("window_did_move_"), "windowDidMove:");
+        assert_eq!(selector("window__did_move"), "window:didMove:");
+        assert_eq!(selector("window_did_expose2"), "windowDidExpose2:");
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::InvalidStyleMask;
+
+/// Errors returned by the plugin.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// No panel is registered for the label.
+    PanelNotFound,
+    /// The window is gone, usually because it was closed.
+    WindowNotFound,
+    /// The window has already been converted to a panel.
+    AlreadyConverted,
+    /// The native `NSWindow` of the window could not be retrieved.
+    NativeHandleUnavailable,
+    /// AppKit may only be driven from the main thread.
+    NotOnMainThread,
+    /// The main thread no longer runs tasks, usually because the app is exiting.
+    MainThreadUnavailable,
+    /// A thread panicked while holding the panel store.
+    StorePoisoned,
+    /// The panel is of another [`PanelKind`](crate::PanelKind).
+    WrongPanelKind {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The scope of the command does not allow driving the panel.
+    PanelNotAllowed,
+    /// The style mask is not supported for panels.
+    InvalidStyleMask(InvalidStyleMask),
+    /// No screen is connected to convert coordinates with.
+    ScreenUnavailable,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PanelNotFound => f.write_str("panel not found"),
+            Self::WindowNotFound => f.write_str("window not found"),
+            Self::AlreadyConverted => f.write_str("window is already a panel"),
+            Self::NativeHandleUnavailable => f.write_str("native window handle is unavailable"),
+            Self::NotOnMainThread => f.write_str("panels must be created on the main thread"),
+            Self::MainThreadUnavailable => f.write_str("main thread is unavailable"),
+            Self::StorePoisoned => f.write_str("panel store is poisoned"),
+            Self::WrongPanelKind { expected, found } => {
+                write!(f, "expected a `{expected}` panel, found a `{found}` panel")
+            }
+            Self::PanelNotAllowed => f.write_str("panel is not allowed by the command scope"),
+            Self::InvalidStyleMask(e) => write!(f, "invalid style mask: {e}"),
+            Self::ScreenUnavailable => f.write_str("no screen is available"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidStyleMask(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// The name of the variant, which the frontend matches errors on.
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::PanelNotFound => "PanelNotFound",
+            Self::WindowNotFound => "WindowNotFound",
+            Self::AlreadyConverted => "AlreadyConverted",
+            Self::NativeHandleUnavailable => "NativeHandleUnavailable",
+            Self::NotOnMainThread => "NotOnMainThread",
+            Self::MainThreadUnavailable => "MainThreadUnavailable",
+            Self::StorePoisoned => "StorePoisoned",
+            Self::WrongPanelKind { .. } => "WrongPanelKind",
+            Self::PanelNotAllowed => "PanelNotAllowed",
+            Self::InvalidStyleMask(_) => "InvalidStyleMask",
+            Self::ScreenUnavailable => "ScreenUnavailable",
+        }
+    }
+}
+
+/// Errors reach the frontend as `{ kind, message }`, `kind` being the name of the variant.
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("Error", 2)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<InvalidStyleMask> for Error {
+    fn from(e: InvalidStyleMask) -> Self {
+        Self::InvalidStyleMask(e)
+    }
+}
+
+impl From<Error> for tauri::Error {
+    fn from(e: Error) -> Self {
+        Self::Anyhow(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_convert_into_tauri_errors() {
+        let error = tauri::Error::from(Error::WrongPanelKind {
+            expected: "RawNSPanel",
+            found: "RawOverlayPanel",
+        });
+
+        assert_eq!(
+            error.to_string(),
+            "expected a `RawNSPanel` panel, found a `RawOverlayPanel` panel"
+        );
+    }
+
+    #[test]
+    fn errors_reach_the_frontend_with_their_kind() {
+        assert_eq!(
+            serde_json::to_value(Error::InvalidStyleMask(InvalidStyleMask::FullScreen)).unwrap(),
+            serde_json::json!({
+                "kind": "InvalidStyleMask",
+                "message": Error::InvalidStyleMask(InvalidStyleMask::FullScreen).to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,761 @@
+#[cfg(target_os = "macos")]
+use cocoa::base::{id, nil};
+#[cfg(target_os = "macos")]
+use objc::{msg_send, sel, sel_impl};
+
+use std::{
+    ffi::c_void,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, EventTarget, Runtime};
+
+use crate::geometry::{Rect, Screen, Size};
+
+/// A `NSWindowDelegate` notification, as delivered to a [`panel_delegate!`](crate::panel_delegate)
+/// listener.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum PanelEvent {
+    DidBecomeKey,
+    DidResignKey,
+    DidBecomeMain,
+    DidResignMain,
+    WillMove,
+    DidMove {
+        frame: Rect,
+    },
+    WillStartLiveResize,
+    DidResize {
+        size: Size,
+    },
+    DidEndLiveResize,
+    DidChangeScreen {
+        screen: Option<Screen>,
+    },
+    DidChangeBackingProperties {
+        scale_factor: f64,
+    },
+    DidChangeOcclusionState {
+        visible: bool,
+    },
+    DidMiniaturize,
+    DidDeminiaturize,
+    WillEnterFullScreen,
+    DidEnterFullScreen,
+    WillExitFullScreen,
+    DidExitFullScreen,
+    WillClose,
+    /// `windowShouldClose:`, reply `false` to keep the panel open.
+    ShouldClose {
+        reply: Reply<bool>,
+    },
+    /// `windowWillResize:toSize:`, reply with the size the panel should take instead.
+    WillResize {
+        size: Size,
+        reply: Reply<Size>,
+    },
+    /// `windowWillUseStandardFrame:defaultFrame:`, reply with the frame to zoom to.
+    WillUseStandardFrame {
+        default_frame: Rect,
+        reply: Reply<Rect>,
+    },
+    /// `window:willPositionSheet:usingRect:`, reply with where `sheet` should appear.
+    WillPositionSheet {
+        sheet: NativeObject,
+        rect: Rect,
+        reply: Reply<Rect>,
+    },
+    /// `windowWillReturnFieldEditor:toObject:`, reply with a custom field editor for `client`.
+    WillReturnFieldEditor {
+        client: NativeObject,
+        reply: Reply<Option<NativeObject>>,
+    },
+    /// A delegate method without a typed event, by its snake case name.
+    Other(String),
+}
+
+/// The answer to a delegate method that returns a value.
+///
+/// Starts out as AppKit's default answer, listeners change it with [`Reply::set`].
+pub struct Reply<T>(Arc<Mutex<T>>);
+
+impl<T: Clone> Reply<T> {
+    pub fn new(default: T) -> Self {
+        Self(Arc::new(Mutex::new(default)))
+    }
+
+    pub fn set(&self, value: T) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = value;
+    }
+
+    pub fn get(&self) -> T {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl<T> Clone for Reply<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for Reply<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Reply").field(&self.get()).finish()
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for Reply<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+/// An Objective-C object handed through an event, only use it on the main thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NativeObject(pub *mut c_void);
+
+// Delegate methods run on the main thread, and so do the listeners receiving these
+unsafe impl Send for NativeObject {}
+unsafe impl Sync for NativeObject {}
+
+/// Identifies a handler registered with [`Panel::on_event`](crate::Panel::on_event).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Handler = Box<dyn Fn(PanelEvent) + Send>;
+
+/// The handlers subscribed to a panel's events, shared by every handle to the panel.
+#[derive(Clone, Default)]
+pub(crate) struct Subscribers(Arc<Mutex<SubscribersInner>>);
+
+#[derive(Default)]
+struct SubscribersInner {
+    next_id: u64,
+    /// Handlers in registration order, a handler is taken out while it runs.
+    handlers: Vec<(SubscriptionId, Option<Handler>)>,
+}
+
+impl Subscribers {
+    fn lock(&self) -> std::sync::MutexGuard<'_, SubscribersInner> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn subscribe(&self, handler: Handler) -> SubscriptionId {
+        let mut inner = self.lock();
+        let id = SubscriptionId(inner.next_id);
+        inner.next_id += 1;
+        inner.handlers.push((id, Some(handler)));
+        id
+    }
+
+    /// Removes the handler, returns `false` if it was not subscribed.
+    pub(crate) fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let removed = {
+            let mut inner = self.lock();
+            let index = inner.handlers.iter().position(|(other, _)| *other == id);
+            index.map(|index| inner.handlers.remove(index))
+        };
+
+        removed.is_some()
+    }
+
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lock().handlers.is_empty()
+    }
+
+    /// Hands `event` to every handler in registration order.
+    ///
+    /// The lock is not held while handlers run, so they may subscribe and unsubscribe. A
+    /// handler does not receive the events raised while it runs.
+    #[cfg_attr(not(any(target_os = "macos", feature = "test-util")), allow(dead_code))]
+    pub(crate) fn emit(&self, event: &PanelEvent) {
+        let ids: Vec<_> = self.lock().handlers.iter().map(|(id, _)| *id).collect();
+
+        for id in ids {
+            let Some(handler) = self.take(id) else {
+                continue;
+            };
+
+            handler(event.clone());
+
+            let mut inner = self.lock();
+            if let Some((_, slot)) = inner.handlers.iter_mut().find(|(other, _)| *other == id) {
+                *slot = Some(handler);
+            }
+        }
+    }
+
+    #[cfg_attr(not(any(target_os = "macos", feature = "test-util")), allow(dead_code))]
+    fn take(&self, id: SubscriptionId) -> Option<Handler> {
+        self.lock()
+            .handlers
+            .iter_mut()
+            .find(|(other, _)| *other == id)
+            .and_then(|(_, handler)| handler.take())
+    }
+}
+
+/// What a forwarded event carries to the frontend.
+#[derive(Clone, Debug, Serialize)]
+struct Forwarded<'a> {
+    label: &'a str,
+    payload: Value,
+}
+
+/// A subscriber emitting every event to the webview `label` as a Tauri event.
+pub(crate) fn forwarder<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> impl Fn(PanelEvent) + Send + 'static {
+    move |event| {
+        let forwarded = Forwarded {
+            label: &label,
+            payload: event.payload(),
+        };
+
+        // The webview may already be gone while the panel closes
+        let _ = app.emit_to(
+            EventTarget::webview_window(label.as_str()),
+            &event.name(),
+            forwarded,
+        );
+    }
+}
+
+/// What events need to know about the panel they come from.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) trait EventSource {
+    fn frame(&self) -> Rect;
+
+    fn screen(&self) -> Option<Screen>;
+
+    fn scale_factor(&self) -> f64;
+
+    fn is_occlusion_visible(&self) -> bool;
+}
+
+impl PanelEvent {
+    /// Builds the event for the delegate method `method`, given in snake case.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn from_method(method: &str, source: &impl EventSource) -> Self {
+        match method {
+            "window_did_become_key" => Self::DidBecomeKey,
+            "window_did_resign_key" => Self::DidResignKey,
+            "window_did_become_main" => Self::DidBecomeMain,
+            "window_did_resign_main" => Self::DidResignMain,
+            "window_will_move" => Self::WillMove,
+            "window_did_move" => Self::DidMove {
+                frame: source.frame(),
+            },
+            "window_will_start_live_resize" => Self::WillStartLiveResize,
+            "window_did_resize" => Self::DidResize {
+                size: source.frame().size,
+            },
+            "window_did_end_live_resize" => Self::DidEndLiveResize,
+            "window_did_change_screen" => Self::DidChangeScreen {
+                screen: source.screen(),
+            },
+            "window_did_change_backing_properties" => Self::DidChangeBackingProperties {
+                scale_factor: source.scale_factor(),
+            },
+            "window_did_change_occlusion_state" => Self::DidChangeOcclusionState {
+                visible: source.is_occlusion_visible(),
+            },
+            "window_did_miniaturize" => Self::DidMiniaturize,
+            "window_did_deminiaturize" => Self::DidDeminiaturize,
+            "window_will_enter_full_screen" => Self::WillEnterFullScreen,
+            "window_did_enter_full_screen" => Self::DidEnterFullScreen,
+            "window_will_exit_full_screen" => Self::WillExitFullScreen,
+            "window_did_exit_full_screen" => Self::DidExitFullScreen,
+            "window_will_close" => Self::WillClose,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+
+    /// The snake case name of the delegate method the event comes from.
+    pub fn method(&self) -> &str {
+        match self {
+            Self::DidBecomeKey => "window_did_become_key",
+            Self::DidResignKey => "window_did_resign_key",
+            Self::DidBecomeMain => "window_did_become_main",
+            Self::DidResignMain => "window_did_resign_main",
+            Self::WillMove => "window_will_move",
+            Self::DidMove { .. } => "window_did_move",
+            Self::WillStartLiveResize => "window_will_start_live_resize",
+            Self::DidResize { .. } => "window_did_resize",
+            Self::DidEndLiveResize => "window_did_end_live_resize",
+            Self::DidChangeScreen { .. } => "window_did_change_screen",
+            Self::DidChangeBackingProperties { .. } => "window_did_change_backing_properties",
+            Self::DidChangeOcclusionState { .. } => "window_did_change_occlusion_state",
+            Self::DidMiniaturize => "window_did_miniaturize",
+            Self::DidDeminiaturize => "window_did_deminiaturize",
+            Self::WillEnterFullScreen => "window_will_enter_full_screen",
+            Self::DidEnterFullScreen => "window_did_enter_full_screen",
+            Self::WillExitFullScreen => "window_will_exit_full_screen",
+            Self::DidExitFullScreen => "window_did_exit_full_screen",
+            Self::WillClose => "window_will_close",
+            Self::ShouldClose { .. } => "window_should_close",
+            Self::WillResize { .. } => "window_will_resize__to_size",
+            Self::WillUseStandardFrame { .. } => "window_will_use_standard_frame__default_frame",
+            Self::WillPositionSheet { .. } => "window__will_position_sheet__using_rect",
+            Self::WillReturnFieldEditor { .. } => "window_will_return_field_editor__to_object",
+            Self::Other(method) => method,
+        }
+    }
+
+    /// The name the event is emitted under when [forwarded](crate::ManagerExt::forward_panel_events)
+    /// to the frontend: the method in kebab case without its `window` prefix, as in
+    /// `nspanel://did-resign-key`.
+    pub fn name(&self) -> String {
+        let method = self.method();
+        let method = method.strip_prefix("window_").unwrap_or(method);
+        let words: Vec<_> = method.split('_').filter(|word| !word.is_empty()).collect();
+
+        format!("nspanel://{}", words.join("-"))
+    }
+
+    /// The data carried by the event, `null` for events without any.
+    ///
+    /// Replies and native objects only make sense in Rust and are left out.
+    pub fn payload(&self) -> Value {
+        match self {
+            Self::DidMove { frame } => json!({ "frame": frame }),
+            Self::DidResize { size } | Self::WillResize { size, .. } => json!({ "size": size }),
+            Self::DidChangeScreen { screen } => json!({ "screen": screen }),
+            Self::DidChangeBackingProperties { scale_factor } => {
+                json!({ "scaleFactor": scale_factor })
+            }
+            Self::DidChangeOcclusionState { visible } => json!({ "visible": visible }),
+            Self::WillUseStandardFrame { default_frame, .. } => {
+                json!({ "defaultFrame": default_frame })
+            }
+            Self::WillPositionSheet { rect, .. } => json!({ "rect": rect }),
+            _ => Value::Null,
+        }
+    }
+
+    /// Builds the event for the delegate method with the Objective-C `selector` from its
+    /// first argument, usually an `NSNotification` about the window.
+    #[cfg(target_os = "macos")]
+    #[doc(hidden)]
+    pub fn from_notification(selector: &str, notification: id) -> Self {
+        Self::from_method(
+            &crate::delegate::method(selector),
+            &NotificationWindow(notified_window(notification)),
+        )
+    }
+}
+
+/// The window a notification was posted for, `nil` if there is none.
+#[cfg(target_os = "macos")]
+struct NotificationWindow(id);
+
+/// Finds the window `argument` is about, it can be a notification or the window itself.
+#[cfg(target_os = "macos")]
+fn notified_window(argument: id) -> id {
+    use cocoa::base::{BOOL, YES};
+    use objc::class;
+
+    if argument == nil {
+        return nil;
+    }
+
+    let is_notification: BOOL =
+        unsafe { msg_send![argument, isKindOfClass: class!(NSNotification)] };
+    let object: id = if is_notification == YES {
+        unsafe { msg_send![argument, object] }
+    } else {
+        argument
+    };
+
+    if object == nil {
+        return nil;
+    }
+
+    let is_window: BOOL = unsafe { msg_send![object, isKindOfClass: class!(NSWindow)] };
+    if is_window == YES {
+        object
+    } else {
+        nil
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl EventSource for NotificationWindow {
+    fn frame(&self) -> Rect {
+        if self.0 == nil {
+            return Rect::default();
+        }
+
+        let frame: cocoa::foundation::NSRect = unsafe { msg_send![self.0, frame] };
+        frame.into()
+    }
+
+    fn screen(&self) -> Option<Screen> {
+        if self.0 == nil {
+            return None;
+        }
+
+        let screen: id = unsafe { msg_send![self.0, screen] };
+        if screen == nil {
+            None
+        } else {
+            Some(unsafe { Screen::from_native(screen) })
+        }
+    }
+
+    fn scale_factor(&self) -> f64 {
+        if self.0 == nil {
+            return 1.0;
+        }
+
+        unsafe { msg_send![self.0, backingScaleFactor] }
+    }
+
+    fn is_occlusion_visible(&self) -> bool {
+        if self.0 == nil {
+            return false;
+        }
+
+        // NSWindowOcclusionStateVisible
+        let state: cocoa::foundation::NSUInteger = unsafe { msg_send![self.0, occlusionState] };
+        state & (1 << 1) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    struct Window;
+
+    impl EventSource for Window {
+        fn frame(&self) -> Rect {
+            Rect::new(10.0, 20.0, 300.0, 200.0)
+        }
+
+        fn screen(&self) -> Option<Screen> {
+            Some(Screen {
+                id: 1,
+                scale_factor: 2.0,
+                ..Default::default()
+            })
+        }
+
+        fn scale_factor(&self) -> f64 {
+            2.0
+        }
+
+        fn is_occlusion_visible(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn events_carry_the_window_state() {
+        assert_eq!(
+            PanelEvent::from_method("window_did_move", &Window),
+            PanelEvent::DidMove {
+                frame: Rect {
+                    origin: Point::new(10.0, 20.0),
+                    size: Size::new(300.0, 200.0),
+                }
+            }
+        );
+        assert_eq!(
+            PanelEvent::from_method("window_did_resize", &Window),
+            PanelEvent::DidResize {
+                size: Size::new(300.0, 200.0)
+            }
+        );
+        assert!(matches!(
+            PanelEvent::from_method("window_did_change_screen", &Window),
+            PanelEvent::DidChangeScreen {
+                screen: Some(Screen { id: 1, .. })
+            }
+        ));
+        assert_eq!(
+            PanelEvent::from_method("window_did_change_occlusion_state", &Window),
+            PanelEvent::DidChangeOcclusionState { visible: true }
+        );
+    }
+
+    #[test]
+    fn unknown_methods_fall_back_to_their_name() {
+        let event = PanelEvent::from_method("window_did_update", &Window);
+
+        assert_eq!(event, PanelEvent::Other("window_did_update".into()));
+        assert_eq!(event.method(), "window_did_update");
+    }
+
+    #[test]
+    fn method_round_trips() {
+        for method in [
+            "window_did_become_key",
+            "window_did_resign_key",
+            "window_did_move",
+            "window_did_resize",
+            "window_did_change_screen",
+            "window_did_change_backing_properties",
+            "window_did_change_occlusion_state",
+            "window_will_enter_full_screen",
+            "window_will_close",
+        ] {
+            let event = PanelEvent::from_method(method, &Window);
+
+            assert!(!matches!(event, PanelEvent::Other(_)), "{method}");
+            assert_eq!(event.method(), method);
+        }
+    }
+
+    #[test]
+    fn replies_are_shared_with_the_listener() {
+        let reply = Reply::new(true);
+        let event = PanelEvent::ShouldClose {
+            reply: reply.clone(),
+        };
+
+        if let PanelEvent::ShouldClose { reply } = &event {
+            reply.set(false);
+        }
+
+        assert!(!reply.get());
+        assert_eq!(event.method(), "window_should_close");
+    }
+
+    #[test]
+    fn reply_methods_name_their_selector() {
+        use crate::delegate::selector;
+
+        let resize = PanelEvent::WillResize {
+            size: Size::default(),
+            reply: Reply::new(Size::default()),
+        };
+        let zoom = PanelEvent::WillUseStandardFrame {
+            default_frame: Rect::default(),
+            reply: Reply::new(Rect::default()),
+        };
+
+        assert_eq!(selector(resize.method()), "windowWillResize:toSize:");
+        assert_eq!(
+            selector(zoom.method()),
+            "windowWillUseStandardFrame:defaultFrame:"
+        );
+    }
+
+    #[test]
+    fn names_are_kebab_cased_methods() {
+        assert_eq!(PanelEvent::DidResignKey.name(), "nspanel://did-resign-key");
+        assert_eq!(
+            PanelEvent::DidMove {
+                frame: Rect::default()
+            }
+            .name(),
+            "nspanel://did-move"
+        );
+        assert_eq!(
+            PanelEvent::ShouldClose {
+                reply: Reply::new(true)
+            }
+            .name(),
+            "nspanel://should-close"
+        );
+        assert_eq!(
+            PanelEvent::Other("window__did_something__with_object".into()).name(),
+            "nspanel://did-something-with-object"
+        );
+        assert_eq!(
+            PanelEvent::Other("window_did_change_title".into()).name(),
+            "nspanel://did-change-title"
+        );
+    }
+
+    #[test]
+    fn payloads_leave_replies_out() {
+        assert_eq!(PanelEvent::DidResignKey.payload(), Value::Null);
+        assert_eq!(
+            PanelEvent::DidMove {
+                frame: Rect::new(1.0, 2.0, 3.0, 4.0)
+            }
+            .payload(),
+            json!({
+                "frame": {
+                    "origin": { "x": 1.0, "y": 2.0 },
+                    "size": { "width": 3.0, "height": 4.0 },
+                }
+            })
+        );
+        assert_eq!(
+            PanelEvent::WillResize {
+                size: Size::new(3.0, 4.0),
+                reply: Reply::new(Size::default()),
+            }
+            .payload(),
+            json!({ "size": { "width": 3.0, "height": 4.0 } })
+        );
+        assert_eq!(
+            PanelEvent::DidChangeScreen {
+                screen: Some(Screen {
+                    id: 2,
+                    scale_factor: 2.0,
+                    ..Default::default()
+                })
+            }
+            .payload()["screen"]["scaleFactor"],
+            json!(2.0)
+        );
+    }
+
+    fn recorder(
+        subscribers: &Subscribers,
+        name: &'static str,
+    ) -> (SubscriptionId, Arc<Mutex<Vec<String>>>) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let received = log.clone();
+        let id = subscribers.subscribe(Box::new(move |event| {
+            received
+                .lock()
+                .unwrap()
+                .push(format!("{name} {}", event.method()))
+        }));
+
+        (id, log)
+    }
+
+    #[test]
+    fn subscribers_are_called_in_registration_order() {
+        let subscribers = Subscribers::default();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for name in ["menu bar", "analytics"] {
+            let order = order.clone();
+            subscribers.subscribe(Box::new(move |_| order.lock().unwrap().push(name)));
+        }
+
+        subscribers.emit(&PanelEvent::DidResignKey);
+
+        assert_eq!(*order.lock().unwrap(), ["menu bar", "analytics"]);
+    }
+
+    #[test]
+    fn unsubscribed_handlers_are_not_called() {
+        let subscribers = Subscribers::default();
+        let (first, first_log) = recorder(&subscribers, "first");
+        let (_, second_log) = recorder(&subscribers, "second");
+
+        subscribers.emit(&PanelEvent::DidBecomeKey);
+        assert!(subscribers.unsubscribe(first));
+        assert!(!subscribers.unsubscribe(first));
+        subscribers.emit(&PanelEvent::DidResignKey);
+
+        assert_eq!(*first_log.lock().unwrap(), ["first window_did_become_key"]);
+        assert_eq!(
+            *second_log.lock().unwrap(),
+            [
+                "second window_did_become_key",
+                "second window_did_resign_key"
+            ]
+        );
+    }
+
+    #[test]
+    fn handlers_can_unsubscribe_while_running() {
+        let subscribers = Subscribers::default();
+        let calls = Arc::new(Mutex::new(0));
+
+        let id = Arc::new(Mutex::new(None));
+        let own_id = id.clone();
+        let own_subscribers = subscribers.clone();
+        let counted = calls.clone();
+        *id.lock().unwrap() = Some(subscribers.subscribe(Box::new(move |_| {
+            *counted.lock().unwrap() += 1;
+            own_subscribers.unsubscribe(own_id.lock().unwrap().unwrap());
+        })));
+
+        subscribers.emit(&PanelEvent::WillClose);
+        subscribers.emit(&PanelEvent::WillClose);
+
+        assert_eq!(*calls.lock().unwrap(), 1);
+        assert!(subscribers.is_empty());
+    }
+
+    #[test]
+    fn nested_events_reach_the_other_handlers() {
+        let subscribers = Subscribers::default();
+        let nested = subscribers.clone();
+        subscribers.subscribe(Box::new(move |event| {
+            if event == PanelEvent::DidResignKey {
+                nested.emit(&PanelEvent::WillClose);
+            }
+        }));
+        let (_, log) = recorder(&subscribers, "other");
+
+        subscribers.emit(&PanelEvent::DidResignKey);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["other window_will_close", "other window_did_resign_key"]
+        );
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn forwarded_events_reach_the_webview() {
+        use tauri::Listener;
+
+        use crate::{
+            test_util,
+            testing::{app, window},
+            Error, ManagerExt, WebviewWindowExt,
+        };
+
+        let app = app(test_util::init());
+        let panel = window(&app, "main").to_panel().unwrap();
+        let payloads = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let received = payloads.clone();
+        app.listen_any("nspanel://did-move", move |event| {
+            let payload: serde_json::Value = serde_json::from_str(event.payload()).unwrap();
+            received.lock().unwrap().push(payload);
+        });
+
+        let forwarding = app.forward_panel_events("main").unwrap();
+        test_util::emit_event(
+            &panel,
+            PanelEvent::DidMove {
+                frame: Rect::new(1.0, 2.0, 3.0, 4.0),
+            },
+        );
+        panel.off(forwarding);
+        test_util::emit_event(
+            &panel,
+            PanelEvent::DidMove {
+                frame: Rect::default(),
+            },
+        );
+
+        assert_eq!(
+            *payloads.lock().unwrap(),
+            [serde_json::json!({
+                "label": "main",
+                "payload": {
+                    "frame": {
+                        "origin": { "x": 1.0, "y": 2.0 },
+                        "size": { "width": 3.0, "height": 4.0 },
+                    }
+                },
+            })]
+        );
+        assert_eq!(
+            app.forward_panel_events("missing").unwrap_err(),
+            Error::PanelNotFound
+        );
+    }
+}
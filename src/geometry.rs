@@ -0,0 +1,251 @@
+#[cfg(target_os = "macos")]
+use cocoa::foundation::{NSPoint, NSRect, NSSize};
+use serde::{Deserialize, Serialize};
+
+/// A point in AppKit screen coordinates, in points with the origin at the bottom left of
+/// the main screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A rectangle in AppKit screen coordinates, see [`Point`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub origin: Point,
+    pub size: Size,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Size {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            origin: Point::new(x, y),
+            size: Size::new(width, height),
+        }
+    }
+
+    pub(crate) fn max_x(&self) -> f64 {
+        self.origin.x + self.size.width
+    }
+
+    pub(crate) fn max_y(&self) -> f64 {
+        self.origin.y + self.size.height
+    }
+
+    /// The area `self` and `other` have in common.
+    pub(crate) fn overlap(&self, other: &Rect) -> f64 {
+        let width = self.max_x().min(other.max_x()) - self.origin.x.max(other.origin.x);
+        let height = self.max_y().min(other.max_y()) - self.origin.y.max(other.origin.y);
+
+        width.max(0.0) * height.max(0.0)
+    }
+
+    /// Moves the rectangle inside `bounds`, shrinking it first if it does not fit.
+    pub(crate) fn clamped_to(&self, bounds: &Rect) -> Rect {
+        let width = self.size.width.min(bounds.size.width);
+        let height = self.size.height.min(bounds.size.height);
+
+        Rect::new(
+            self.origin
+                .x
+                .min(bounds.max_x() - width)
+                .max(bounds.origin.x),
+            self.origin
+                .y
+                .min(bounds.max_y() - height)
+                .max(bounds.origin.y),
+            width,
+            height,
+        )
+    }
+}
+
+impl Screen {
+    /// The screen showing the largest part of `frame`, the way AppKit picks a window's screen.
+    pub(crate) fn for_frame(frame: &Rect, screens: &[Screen]) -> Option<Screen> {
+        screens
+            .iter()
+            .map(|screen| (screen.frame.overlap(frame), screen))
+            .filter(|(overlap, _)| *overlap > 0.0)
+            .fold(None, |best: Option<(f64, &Screen)>, candidate| match best {
+                Some(best) if best.0 >= candidate.0 => Some(best),
+                _ => Some(candidate),
+            })
+            .map(|(_, screen)| *screen)
+    }
+}
+
+/// A display, as reported by `NSScreen`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Screen {
+    /// The display's `NSScreenNumber`, its `CGDirectDisplayID`.
+    pub id: u32,
+    pub frame: Rect,
+    /// The frame minus the menu bar and the Dock.
+    pub visible_frame: Rect,
+    pub scale_factor: f64,
+}
+
+#[cfg(target_os = "macos")]
+impl From<NSPoint> for Point {
+    fn from(point: NSPoint) -> Self {
+        Self::new(point.x, point.y)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<Point> for NSPoint {
+    fn from(point: Point) -> Self {
+        NSPoint::new(point.x, point.y)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<NSSize> for Size {
+    fn from(size: NSSize) -> Self {
+        Self::new(size.width, size.height)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<Size> for NSSize {
+    fn from(size: Size) -> Self {
+        NSSize::new(size.width, size.height)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<NSRect> for Rect {
+    fn from(rect: NSRect) -> Self {
+        Self {
+            origin: rect.origin.into(),
+            size: rect.size.into(),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<Rect> for NSRect {
+    fn from(rect: Rect) -> Self {
+        NSRect::new(rect.origin.into(), rect.size.into())
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Screen {
+    /// Reads the screen's properties.
+    ///
+    /// # Safety
+    ///
+    /// `screen` must be a valid `NSScreen`.
+    pub(crate) unsafe fn from_native(screen: cocoa::base::id) -> Self {
+        use cocoa::{
+            base::nil,
+            foundation::{NSAutoreleasePool, NSString},
+        };
+        use objc::{msg_send, sel, sel_impl};
+
+        let frame: NSRect = msg_send![screen, frame];
+        let visible_frame: NSRect = msg_send![screen, visibleFrame];
+        let scale_factor: f64 = msg_send![screen, backingScaleFactor];
+
+        let description: cocoa::base::id = msg_send![screen, deviceDescription];
+        let key = NSString::alloc(nil)
+            .init_str("NSScreenNumber")
+            .autorelease();
+        let number: cocoa::base::id = msg_send![description, objectForKey: key];
+        let id: u32 = if number == nil {
+            0
+        } else {
+            msg_send![number, unsignedIntValue]
+        };
+
+        Self {
+            id,
+            frame: frame.into(),
+            visible_frame: visible_frame.into(),
+            scale_factor,
+        }
+    }
+
+    /// Every connected screen, the one holding the menu bar first.
+    pub(crate) fn all() -> Vec<Self> {
+        use cocoa::foundation::NSUInteger;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let screens: cocoa::base::id = msg_send![class!(NSScreen), screens];
+            let count: NSUInteger = msg_send![screens, count];
+
+            (0..count)
+                .map(|index| Self::from_native(msg_send![screens, objectAtIndex: index]))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(id: u32, frame: Rect) -> Screen {
+        Screen {
+            id,
+            frame,
+            visible_frame: frame,
+            scale_factor: 2.0,
+        }
+    }
+
+    #[test]
+    fn rects_are_clamped_inside_their_bounds() {
+        let bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+
+        assert_eq!(
+            Rect::new(10.0, 10.0, 20.0, 20.0).clamped_to(&bounds),
+            Rect::new(10.0, 10.0, 20.0, 20.0)
+        );
+        assert_eq!(
+            Rect::new(90.0, -5.0, 20.0, 20.0).clamped_to(&bounds),
+            Rect::new(80.0, 0.0, 20.0, 20.0)
+        );
+        assert_eq!(
+            Rect::new(-50.0, 50.0, 200.0, 20.0).clamped_to(&bounds),
+            Rect::new(0.0, 50.0, 100.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn the_screen_showing_most_of_the_frame_wins() {
+        let screens = [
+            screen(1, Rect::new(0.0, 0.0, 100.0, 100.0)),
+            screen(2, Rect::new(100.0, 0.0, 100.0, 100.0)),
+        ];
+
+        let on = |frame| Screen::for_frame(&frame, &screens).map(|screen| screen.id);
+
+        assert_eq!(on(Rect::new(10.0, 10.0, 10.0, 10.0)), Some(1));
+        assert_eq!(on(Rect::new(80.0, 10.0, 50.0, 10.0)), Some(2));
+        assert_eq!(on(Rect::new(300.0, 10.0, 10.0, 10.0)), None);
+    }
+}
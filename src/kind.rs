@@ -0,0 +1,59 @@
+use crate::{backend::PanelBackend, CollectionBehavior, PanelLevel, PanelStyleMask};
+
+/// Describes a kind of panel.
+///
+/// A kind only decides how the panel's class overrides `NSPanel` and how a freshly converted
+/// window is configured, every kind shares the same [`Panel`](crate::Panel) API.
+pub trait PanelKind: Send + Sync + 'static {
+    /// Name of the Objective-C class windows of this kind are converted to, must be unique.
+    const CLASS_NAME: &'static str;
+
+    /// Value returned by `canBecomeKeyWindow`.
+    const CAN_BECOME_KEY_WINDOW: bool = true;
+
+    /// Value returned by `canBecomeMainWindow`, `None` keeps `NSPanel`'s behaviour.
+    const CAN_BECOME_MAIN_WINDOW: Option<bool> = None;
+
+    /// Value returned by `acceptsMouseMovedEvents`, `None` keeps `NSPanel`'s behaviour.
+    const ACCEPTS_MOUSE_MOVED_EVENTS: Option<bool> = None;
+
+    /// Value returned by `ignoresMouseEvents`, `None` keeps `NSPanel`'s behaviour.
+    const IGNORES_MOUSE_EVENTS: Option<bool> = None;
+
+    /// Called once, right after a window has been converted to this kind.
+    fn configure(_panel: &dyn PanelBackend) {}
+}
+
+/// A regular panel that can become the key window.
+pub struct Standard;
+
+impl PanelKind for Standard {
+    const CLASS_NAME: &'static str = "RawNSPanel";
+}
+
+/// A non-activating panel floating above everything else, including full screen apps.
+pub struct Overlay;
+
+impl PanelKind for Overlay {
+    const CLASS_NAME: &'static str = "RawOverlayPanel";
+    const CAN_BECOME_KEY_WINDOW: bool = false;
+    const CAN_BECOME_MAIN_WINDOW: Option<bool> = Some(false);
+    const ACCEPTS_MOUSE_MOVED_EVENTS: Option<bool> = Some(true);
+    const IGNORES_MOUSE_EVENTS: Option<bool> = Some(false);
+
+    fn configure(panel: &dyn PanelBackend) {
+        // Set up non-activating behavior
+        panel.set_style_mask(PanelStyleMask::NONACTIVATING_PANEL);
+        panel.set_level(PanelLevel::Status.offset(1));
+        panel.set_floating_panel(true);
+        panel.set_accepts_mouse_moved_events(true);
+        panel.set_ignores_mouse_events(false);
+        panel.set_hides_on_deactivate(false);
+        panel.set_movable_by_window_background(true);
+        panel.set_collection_behaviour(
+            CollectionBehavior::FULL_SCREEN_AUXILIARY
+                | CollectionBehavior::CAN_JOIN_ALL_SPACES
+                | CollectionBehavior::IGNORES_CYCLE,
+        );
+    }
+}
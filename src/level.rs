@@ -0,0 +1,251 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer,
+};
+
+/// The level of a panel, deciding which windows it is drawn above.
+///
+/// The named levels match AppKit's `NSWindowLevel` constants. Levels compare by their
+/// numeric [`value`](PanelLevel::value), so `PanelLevel::Status.offset(1)` equals
+/// `PanelLevel::Custom(26)`.
+#[derive(Clone, Debug, Default)]
+pub enum PanelLevel {
+    /// `NSNormalWindowLevel`
+    #[default]
+    Normal,
+    /// `NSFloatingWindowLevel`, also used by torn off menus and submenus
+    Floating,
+    /// `NSModalPanelWindowLevel`
+    Modal,
+    /// `kCGDockWindowLevel`
+    Dock,
+    /// `NSMainMenuWindowLevel`
+    MainMenu,
+    /// `NSStatusWindowLevel`
+    Status,
+    /// `NSPopUpMenuWindowLevel`
+    PopUpMenu,
+    /// `NSScreenSaverWindowLevel`
+    ScreenSaver,
+    /// The level of the window shielding the display when it is captured
+    Shielding,
+    /// A raw `NSWindowLevel`
+    Custom(i32),
+    /// A level `offset` levels above (or below, when negative) another level
+    Relative(Box<PanelLevel>, i32),
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGShieldingWindowLevel() -> i32;
+}
+
+#[cfg(target_os = "macos")]
+fn shielding_level() -> i32 {
+    unsafe { CGShieldingWindowLevel() }
+}
+
+// What `CGShieldingWindowLevel` returns on current macOS releases
+#[cfg(not(target_os = "macos"))]
+fn shielding_level() -> i32 {
+    i32::MAX - 19
+}
+
+impl PanelLevel {
+    /// The level `offset` levels above this one.
+    pub fn offset(self, offset: i32) -> Self {
+        Self::Relative(Box::new(self), offset)
+    }
+
+    /// The numeric `NSWindowLevel`.
+    pub fn value(&self) -> i32 {
+        match self {
+            Self::Normal => 0,
+            Self::Floating => 3,
+            Self::Modal => 8,
+            Self::Dock => 20,
+            Self::MainMenu => 24,
+            Self::Status => 25,
+            Self::PopUpMenu => 101,
+            Self::ScreenSaver => 1000,
+            Self::Shielding => shielding_level(),
+            Self::Custom(value) => *value,
+            Self::Relative(base, offset) => base.value().saturating_add(*offset),
+        }
+    }
+
+    /// Maps a numeric `NSWindowLevel` back to a named level, or [`PanelLevel::Custom`].
+    pub fn from_value(value: i32) -> Self {
+        [
+            Self::Normal,
+            Self::Floating,
+            Self::Modal,
+            Self::Dock,
+            Self::MainMenu,
+            Self::Status,
+            Self::PopUpMenu,
+            Self::ScreenSaver,
+            Self::Shielding,
+        ]
+        .into_iter()
+        .find(|level| level.value() == value)
+        .unwrap_or(Self::Custom(value))
+    }
+}
+
+/// The names levels are deserialized from, in the order of [`PanelLevel::from_value`].
+const NAMES: &[&str] = &[
+    "normal",
+    "floating",
+    "modal",
+    "dock",
+    "mainMenu",
+    "status",
+    "popUpMenu",
+    "screenSaver",
+    "shielding",
+];
+
+/// Levels are deserialized from their camel case name, as in `"popUpMenu"`, or from a raw
+/// `NSWindowLevel`.
+impl<'de> Deserialize<'de> for PanelLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LevelVisitor;
+
+        impl Visitor<'_> for LevelVisitor {
+            type Value = PanelLevel;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a panel level name or a window level number")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<PanelLevel, E> {
+                match name {
+                    "normal" => Ok(PanelLevel::Normal),
+                    "floating" => Ok(PanelLevel::Floating),
+                    "modal" => Ok(PanelLevel::Modal),
+                    "dock" => Ok(PanelLevel::Dock),
+                    "mainMenu" => Ok(PanelLevel::MainMenu),
+                    "status" => Ok(PanelLevel::Status),
+                    "popUpMenu" => Ok(PanelLevel::PopUpMenu),
+                    "screenSaver" => Ok(PanelLevel::ScreenSaver),
+                    "shielding" => Ok(PanelLevel::Shielding),
+                    _ => Err(E::unknown_variant(name, NAMES)),
+                }
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<PanelLevel, E> {
+                i32::try_from(value)
+                    .map(PanelLevel::from_value)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<PanelLevel, E> {
+                i32::try_from(value)
+                    .map(PanelLevel::from_value)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(LevelVisitor)
+    }
+}
+
+impl From<i32> for PanelLevel {
+    fn from(value: i32) -> Self {
+        Self::from_value(value)
+    }
+}
+
+impl From<PanelLevel> for i32 {
+    fn from(level: PanelLevel) -> Self {
+        level.value()
+    }
+}
+
+impl PartialEq for PanelLevel {
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+impl Eq for PanelLevel {}
+
+impl Hash for PanelLevel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value().hash(state);
+    }
+}
+
+impl PartialOrd for PanelLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PanelLevel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value().cmp(&other.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_levels_match_appkit() {
+        assert_eq!(PanelLevel::Normal.value(), 0);
+        assert_eq!(PanelLevel::Floating.value(), 3);
+        assert_eq!(PanelLevel::Modal.value(), 8);
+        assert_eq!(PanelLevel::MainMenu.value(), 24);
+        assert_eq!(PanelLevel::Status.value(), 25);
+        assert_eq!(PanelLevel::PopUpMenu.value(), 101);
+        assert_eq!(PanelLevel::ScreenSaver.value(), 1000);
+    }
+
+    #[test]
+    fn relative_levels_add_their_offset() {
+        assert_eq!(PanelLevel::Status.offset(1).value(), 26);
+        assert_eq!(PanelLevel::Floating.offset(-3).value(), 0);
+        assert_eq!(PanelLevel::Shielding.offset(i32::MAX).value(), i32::MAX);
+    }
+
+    #[test]
+    fn from_value_prefers_named_levels() {
+        assert!(matches!(PanelLevel::from_value(25), PanelLevel::Status));
+        assert!(matches!(PanelLevel::from_value(26), PanelLevel::Custom(26)));
+        assert!(matches!(
+            PanelLevel::from_value(PanelLevel::Shielding.value()),
+            PanelLevel::Shielding
+        ));
+    }
+
+    #[test]
+    fn levels_deserialize_from_names_and_values() {
+        let level = |json: &str| serde_json::from_str::<PanelLevel>(json);
+
+        assert!(matches!(level(r#""popUpMenu""#), Ok(PanelLevel::PopUpMenu)));
+        assert!(matches!(level("25"), Ok(PanelLevel::Status)));
+        assert!(matches!(level("-1"), Ok(PanelLevel::Custom(-1))));
+        assert!(level("4294967296").is_err());
+        assert!(level(r#""above""#)
+            .unwrap_err()
+            .to_string()
+            .starts_with("unknown variant `above`, expected one of `normal`"));
+    }
+
+    #[test]
+    fn levels_compare_by_value() {
+        assert_eq!(PanelLevel::Status.offset(1), PanelLevel::Custom(26));
+        assert_eq!(PanelLevel::Custom(0), PanelLevel::Normal);
+        assert!(PanelLevel::PopUpMenu > PanelLevel::Status.offset(1));
+    }
+}
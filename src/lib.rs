@@ -1,39 +1,80 @@
+mod animation;
+pub mod backend;
+mod builder;
+mod collection_behavior;
+mod commands;
+mod config;
+pub mod coordinates;
+#[doc(hidden)]
+pub mod delegate;
+mod error;
+mod event;
+mod geometry;
+pub mod kind;
+mod level;
 mod macros;
-pub mod raw_nspanel;
-pub mod raw_overlaypanel;
+mod main_thread;
+pub mod memory_panel;
+mod panel;
+mod persistence;
+pub mod placement;
+#[cfg(target_os = "macos")]
+pub mod raw_panel;
+mod scope;
+mod show_target;
+mod style_mask;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(test)]
+mod testing;
 
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
 
-use cocoa::base::id;
-use objc_id::ShareId;
-use raw_nspanel::RawNSPanel;
-use raw_overlaypanel::RawOverlayPanel;
+use main_thread::MainThread;
+#[cfg(not(target_os = "macos"))]
+use memory_panel::MemoryPanel;
+#[cfg(target_os = "macos")]
+use raw_panel::RawPanel;
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Manager, Runtime, WebviewWindow,
+    Manager, RunEvent, Runtime, WebviewWindow, WindowEvent,
 };
 
+pub use animation::{Animation, Easing, Transition};
+pub use backend::PanelBackend;
+pub use builder::{PanelBuilder, WebviewPanelConfig};
+pub use collection_behavior::CollectionBehavior;
+pub use config::{Config, ConfiguredKind, PanelConfig};
+pub use error::Error;
+pub use event::{NativeObject, PanelEvent, Reply, SubscriptionId};
+pub use geometry::{Point, Rect, Screen, Size};
+pub use kind::{Overlay, PanelKind, Standard};
+pub use level::PanelLevel;
+pub use panel::Panel;
+pub use placement::{Alignment, Edge, Placement, PlacementOptions};
+pub use show_target::ShowTarget;
+pub use style_mask::{InvalidStyleMask, PanelStyleMask};
+
+#[cfg(target_os = "macos")]
 pub extern crate block;
+#[cfg(target_os = "macos")]
 pub extern crate cocoa;
+#[cfg(target_os = "macos")]
 pub extern crate objc;
+#[cfg(target_os = "macos")]
 pub extern crate objc_foundation;
+#[cfg(target_os = "macos")]
 pub extern crate objc_id;
 pub extern crate tauri;
 
-pub type Panel = ShareId<RawNSPanel>;
-pub type OverlayPanel = ShareId<RawOverlayPanel>;
-
 #[derive(Default)]
 pub struct Store {
-    panels: HashMap<String, ShareId<RawNSPanel>>,
+    panels: HashMap<String, Panel>,
 }
 
-#[derive(Default)]
-pub struct OverlayPanelStore {
-    panels: HashMap<String, ShareId<RawOverlayPanel>>,
-}
-
-
 pub struct WebviewPanelManager(pub Mutex<Store>);
 
 impl Default for WebviewPanelManager {
@@ -42,28 +83,52 @@ impl Default for WebviewPanelManager {
     }
 }
 
-pub struct OverlayPanelManager(pub Mutex<OverlayPanelStore>);
-
-impl Default for OverlayPanelManager {
-    fn default() -> Self {
-        Self(Mutex::new(OverlayPanelStore::default()))
+impl WebviewPanelManager {
+    fn lock(&self) -> Result<MutexGuard<'_, Store>, Error> {
+        self.0.lock().map_err(|_| Error::StorePoisoned)
     }
 }
 
 pub trait ManagerExt<R: Runtime> {
-    fn get_webview_panel(&self, label: &str) -> Result<ShareId<RawNSPanel>, Error>;
-    fn get_webview_overlay_panel(&self, label: &str) -> Result<ShareId<RawOverlayPanel>, Error>;
-}
+    /// Returns the panel of the window with the given label, whatever its [`PanelKind`].
+    fn get_webview_panel(&self, label: &str) -> Result<Panel, Error>;
+
+    /// Returns the panel of the window with the given label, failing with
+    /// [`Error::WrongPanelKind`] unless it is of kind `K`.
+    fn get_webview_panel_of<K: PanelKind>(&self, label: &str) -> Result<Panel, Error> {
+        self.get_webview_panel(label)?.of_kind::<K>()
+    }
+
+    /// Emits every delegate event of the panel as a Tauri event to its own webview, such as
+    /// `nspanel://did-resign-key` with `{ label, payload }`.
+    ///
+    /// Events are named after [`PanelEvent::name`] and carry [`PanelEvent::payload`]. Like any
+    /// [subscriber](Panel::on_event), forwarding stops once the returned subscription is
+    /// passed to [`Panel::off`].
+    ///
+    /// A panel without a [`panel_delegate!`] delegate is given one delivering every
+    /// notification, such as `window_did_move`. Setting a delegate that is not a
+    /// [`panel_delegate!`] delegate afterwards stops the events.
+    fn forward_panel_events(&self, label: &str) -> Result<SubscriptionId, Error>;
 
-#[derive(Debug)]
-pub enum Error {
-    PanelNotFound,
+    /// Restores the frame the panel had when the app last ran, then saves it to the app data
+    /// directory whenever the panel moves or resizes outside of an animation.
+    ///
+    /// Call it right after converting the window. A panel whose screen was disconnected since
+    /// is moved to the screen holding the menu bar, and it is always kept inside the visible
+    /// frame of its screen.
+    fn remember_panel_frame(&self, label: &str) -> Result<(), Error>;
+
+    /// Forgets the panel of the window with the given label and returns it.
+    ///
+    /// Panels are removed automatically once their window is destroyed.
+    fn remove_panel(&self, label: &str) -> Result<Panel, Error>;
 }
 
 impl<R: Runtime, T: Manager<R>> ManagerExt<R> for T {
-    fn get_webview_panel(&self, label: &str) -> Result<ShareId<RawNSPanel>, Error> {
+    fn get_webview_panel(&self, label: &str) -> Result<Panel, Error> {
         let manager = self.state::<self::WebviewPanelManager>();
-        let manager = manager.0.lock().unwrap();
+        let manager = manager.lock()?;
 
         match manager.panels.get(label) {
             Some(panel) => Ok(panel.clone()),
@@ -71,65 +136,384 @@ impl<R: Runtime, T: Manager<R>> ManagerExt<R> for T {
         }
     }
 
-    fn get_webview_overlay_panel(&self, label: &str) -> Result<ShareId<RawOverlayPanel>, Error> {
-        let manager = self.state::<self::OverlayPanelManager>();
-        let manager = manager.0.lock().unwrap();
+    fn forward_panel_events(&self, label: &str) -> Result<SubscriptionId, Error> {
+        let panel = self.get_webview_panel(label)?;
 
-        match manager.panels.get(label) {
-            Some(panel) => Ok(panel.clone()),
-            None => Err(Error::PanelNotFound),
-        }
+        #[cfg(target_os = "macos")]
+        panel.deliver_events();
+
+        Ok(panel.on_event(event::forwarder(
+            self.app_handle().clone(),
+            label.to_owned(),
+        )))
     }
-}
 
-#[derive(Default)]
-pub struct WebviewPanelConfig {
-    pub delegate: Option<id>,
+    fn remember_panel_frame(&self, label: &str) -> Result<(), Error> {
+        persistence::remember(self, label)
+    }
+
+    fn remove_panel(&self, label: &str) -> Result<Panel, Error> {
+        let manager = self.state::<self::WebviewPanelManager>();
+        let mut manager = manager.lock()?;
+
+        manager.panels.remove(label).ok_or(Error::PanelNotFound)
+    }
 }
 
 pub trait WebviewWindowExt<R: Runtime> {
-    fn to_panel(&self) -> tauri::Result<ShareId<RawNSPanel>>;
-    fn to_overlay_panel(&self) -> tauri::Result<ShareId<RawOverlayPanel>>;
+    /// Converts the window to a [`Standard`] panel.
+    fn to_panel(&self) -> Result<Panel, Error> {
+        self.to_panel_of::<Standard>()
+    }
+
+    /// Converts the window to an [`Overlay`] panel.
+    fn to_overlay_panel(&self) -> Result<Panel, Error> {
+        self.to_panel_of::<Overlay>()
+    }
+
+    /// Converts the window to a panel of kind `K`.
+    ///
+    /// Converting a window again returns the existing panel, or fails with
+    /// [`Error::WrongPanelKind`] if it was converted to another kind. Called off the main
+    /// thread, it waits for the conversion to run on the main thread.
+    fn to_panel_of<K: PanelKind>(&self) -> Result<Panel, Error>;
 }
 
 impl<R: Runtime> WebviewWindowExt<R> for WebviewWindow<R> {
-    fn to_panel(&self) -> tauri::Result<ShareId<RawNSPanel>> {
-        let panel = RawNSPanel::from_window(self.to_owned());
-        let shared_panel = panel.share();
-        let manager = self.state::<self::WebviewPanelManager>();
+    fn to_panel_of<K: PanelKind>(&self) -> Result<Panel, Error> {
+        if self.get_webview_window(self.label()).is_none() {
+            return Err(Error::WindowNotFound);
+        }
 
-        manager
-            .0
-            .lock()
-            .unwrap()
-            .panels
-            .insert(self.label().into(), shared_panel.clone());
+        let main_thread = self.state::<MainThread>().inner().clone();
+        let window = self.clone();
 
-        Ok(shared_panel)
+        // AppKit windows can only be converted on the main thread, running every conversion
+        // there also keeps a window from being converted twice concurrently
+        main_thread
+            .clone()
+            .run(move || convert::<K, R>(&window, main_thread))?
     }
-    fn to_overlay_panel(&self) -> tauri::Result<ShareId<RawOverlayPanel>> {
-        let panel = RawOverlayPanel::from_window(self.to_owned());
-        let shared_panel = panel.share();
-        let manager = self.state::<self::OverlayPanelManager>();
+}
 
-        manager
-            .0
-            .lock()
-            .unwrap()
-            .panels
-            .insert(self.label().into(), shared_panel.clone());
+/// Converts `window` and registers the panel, or returns the panel it already is.
+///
+/// Must be called on the main thread.
+fn convert<K: PanelKind, R: Runtime>(
+    window: &WebviewWindow<R>,
+    main_thread: MainThread,
+) -> Result<Panel, Error> {
+    let manager = window.state::<WebviewPanelManager>();
+
+    let existing = manager.lock()?.panels.get(window.label()).cloned();
+    if let Some(panel) = existing {
+        return panel.of_kind::<K>();
+    }
+
+    let conversion = match *window.state::<Backend>() {
+        Backend::Native => native_backend::<K, R>(window)?,
+        #[cfg(feature = "test-util")]
+        Backend::Fake => Conversion::New(test_util::fake_backend::<K, R>(window)),
+    };
+
+    let backend = match conversion {
+        Conversion::New(backend) => {
+            K::configure(&*backend);
+            backend
+        }
+        Conversion::Existing(backend) => backend,
+    };
+
+    let panel = Panel::on_main_thread::<K>(backend, main_thread);
+
+    manager
+        .lock()?
+        .panels
+        .insert(window.label().into(), panel.clone());
+
+    Ok(panel)
+}
+
+/// Which backend panels are created with.
+pub(crate) enum Backend {
+    Native,
+    #[cfg(feature = "test-util")]
+    Fake,
+}
 
-        Ok(shared_panel)
+/// The backend of a window being turned into a panel.
+pub(crate) enum Conversion {
+    /// The window was just converted and still has to be configured.
+    New(Arc<dyn PanelBackend>),
+    /// The native window had already been converted to the requested kind.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Existing(Arc<dyn PanelBackend>),
+}
+
+#[cfg(target_os = "macos")]
+fn native_backend<K: PanelKind, R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Result<Conversion, Error> {
+    if let Some(panel) = RawPanel::<K>::existing(window)? {
+        return Ok(Conversion::Existing(Arc::new(panel.share())));
     }
+
+    Ok(Conversion::New(Arc::new(
+        RawPanel::<K>::from_window(window.to_owned())?.share(),
+    )))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn native_backend<K: PanelKind, R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Result<Conversion, Error> {
+    Ok(Conversion::New(Arc::new(MemoryPanel::from_window::<K, R>(
+        window.to_owned(),
+    ))))
 }
 
 /// Initializes the plugin.
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("nspanel")
-        .setup(|app, _api| {
+///
+/// Windows declared in the plugin's [`Config`] are converted to panels as soon as their
+/// webview is ready.
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
+    plugin(Backend::Native)
+}
+
+pub(crate) fn plugin<R: Runtime>(backend: Backend) -> TauriPlugin<R, Option<Config>> {
+    Builder::<R, Option<Config>>::new("nspanel")
+        .invoke_handler(tauri::generate_handler![
+            commands::show,
+            commands::hide,
+            commands::toggle,
+            commands::close,
+            commands::set_level,
+            commands::set_alpha,
+            commands::set_frame,
+            commands::is_visible,
+            commands::show_animated,
+            commands::hide_animated,
+        ])
+        .setup(|app, api| {
+            // Tauri deserializes the configuration, an absent one is `null`
+            let config = api.config().clone().unwrap_or_default();
+            config
+                .validate()
+                .map_err(|e| format!("invalid `plugins.nspanel` configuration: {e}"))?;
+
+            app.manage(backend);
+            app.manage(MainThread::new(app.clone()));
             app.manage(self::WebviewPanelManager::default());
-            app.manage(self::OverlayPanelManager::default());
+            app.manage(config);
+            app.manage(persistence::FrameStore::new(
+                app.path()
+                    .app_data_dir()
+                    .ok()
+                    .map(|dir| dir.join(persistence::FILE_NAME)),
+            ));
             Ok(())
         })
+        .on_webview_ready(|webview| {
+            let config = webview.state::<Config>();
+            let label = webview.label();
+
+            let Some(panel) = config.panels.get(label) else {
+                return;
+            };
+            let Some(window) = webview.get_webview_window(label) else {
+                return;
+            };
+
+            if let Err(e) = panel.convert(&window) {
+                log::error!("failed to convert the `{label}` window to a panel: {e}");
+            }
+        })
+        .on_event(|app, event| match event {
+            RunEvent::WindowEvent { label, event, .. } => {
+                match event {
+                    WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+                        persistence::save(app, label);
+                    }
+                    WindowEvent::Destroyed => {
+                        // Don't hand out panels whose window is gone
+                        let _ = app.remove_panel(label);
+
+                        let frames = app.state::<persistence::FrameStore>();
+                        frames.forget(label);
+                        frames.flush();
+                    }
+                    _ => (),
+                }
+            }
+            RunEvent::ExitRequested { .. } | RunEvent::Exit => {
+                app.state::<persistence::FrameStore>().flush();
+            }
+            _ => (),
+        })
         .build()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{app, window};
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn to_panel_uses_the_memory_backend() {
+        let app = app(init());
+        let panel = window(&app, "main").to_panel().unwrap();
+
+        panel.order_out();
+
+        let stored = app.get_webview_panel("main").unwrap();
+        assert!(stored.ptr_eq(&panel));
+        assert!(!stored.is_visible());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn to_panel_registers_the_panel() {
+        let app = app(test_util::init());
+        window(&app, "main").to_panel().unwrap();
+
+        app.get_webview_panel("main")
+            .unwrap()
+            .set_level(PanelLevel::Floating);
+
+        let fake = test_util::fake_panel(&app, "main").unwrap();
+        fake.assert_calls(&[test_util::PanelCall::SetLevel(PanelLevel::Floating)]);
+        fake.assert_level(PanelLevel::Floating);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn panel_lookup_fails_for_unknown_label() {
+        let app = app(test_util::init());
+        window(&app, "main").to_panel().unwrap();
+
+        assert!(matches!(
+            app.get_webview_panel("other"),
+            Err(Error::PanelNotFound)
+        ));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn overlay_panel_is_configured_like_the_native_one() {
+        let app = app(test_util::init());
+        window(&app, "overlay").to_overlay_panel().unwrap();
+
+        let fake = test_util::fake_panel(&app, "overlay").unwrap();
+        fake.assert_level(PanelLevel::Status.offset(1));
+        fake.assert_style_mask(PanelStyleMask::NONACTIVATING_PANEL);
+        assert!(fake.state().floating);
+        fake.clear_calls();
+
+        let panel = app.get_webview_panel("overlay").unwrap();
+        assert!(panel.is::<Overlay>());
+
+        panel.show();
+
+        fake.assert_calls(&[test_util::PanelCall::Show]);
+        fake.assert_visible();
+        assert!(!fake.state().key);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn one_lookup_serves_every_kind() {
+        let app = app(test_util::init());
+        window(&app, "main").to_panel().unwrap();
+        window(&app, "overlay").to_overlay_panel().unwrap();
+
+        assert!(app.get_webview_panel("main").unwrap().is::<Standard>());
+        assert!(app.get_webview_panel("overlay").unwrap().is::<Overlay>());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn converting_twice_returns_the_existing_panel() {
+        let app = app(test_util::init());
+        let window = window(&app, "main");
+        let panel = window.to_panel().unwrap();
+        panel.set_level(PanelLevel::Floating);
+
+        assert!(window.to_panel().unwrap().ptr_eq(&panel));
+
+        // The panel is not configured again
+        let fake = test_util::fake_panel(&app, "main").unwrap();
+        fake.assert_calls(&[test_util::PanelCall::SetLevel(PanelLevel::Floating)]);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn windows_can_be_converted_from_other_threads() {
+        let app = app(test_util::init());
+        let window = window(&app, "hud");
+
+        let panel = std::thread::spawn(move || window.to_panel_of::<Overlay>())
+            .join()
+            .unwrap()
+            .unwrap();
+
+        assert!(app
+            .get_webview_panel_of::<Overlay>("hud")
+            .unwrap()
+            .ptr_eq(&panel));
+        test_util::fake_panel(&app, "hud")
+            .unwrap()
+            .assert_level(PanelLevel::Status.offset(1));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn converting_to_another_kind_fails() {
+        let app = app(test_util::init());
+        let window = window(&app, "main");
+        window.to_panel().unwrap();
+
+        assert_eq!(
+            window.to_overlay_panel().unwrap_err(),
+            Error::WrongPanelKind {
+                expected: Overlay::CLASS_NAME,
+                found: Standard::CLASS_NAME,
+            }
+        );
+        assert!(app.get_webview_panel("main").unwrap().is::<Standard>());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn typed_lookup_checks_the_kind() {
+        let app = app(test_util::init());
+        window(&app, "overlay").to_overlay_panel().unwrap();
+
+        assert!(app.get_webview_panel_of::<Overlay>("overlay").is_ok());
+        assert_eq!(
+            app.get_webview_panel_of::<Standard>("overlay").unwrap_err(),
+            Error::WrongPanelKind {
+                expected: Standard::CLASS_NAME,
+                found: Overlay::CLASS_NAME,
+            }
+        );
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn removed_panels_are_no_longer_found() {
+        let app = app(test_util::init());
+        let window = window(&app, "main");
+        let panel = window.to_panel().unwrap();
+
+        assert!(app.remove_panel("main").unwrap().ptr_eq(&panel));
+        assert_eq!(
+            app.get_webview_panel("main").unwrap_err(),
+            Error::PanelNotFound
+        );
+        assert_eq!(app.remove_panel("main").unwrap_err(), Error::PanelNotFound);
+
+        // The window can be converted again
+        assert!(!window.to_panel().unwrap().ptr_eq(&panel));
+    }
+}
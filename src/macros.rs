@@ -1,6 +1,6 @@
 #[macro_export]
 macro_rules! panel_delegate {
-    ($delegate_name:ident { $($fn_name:ident),* $(,)* }) => {{
+    ($delegate_name:ident { $($fn_name:ident $(= $selector:tt)?),* $(,)* }) => {{
         use $crate::objc::{
             class,
             declare::ClassDecl,
@@ -8,40 +8,10 @@ macro_rules! panel_delegate {
             runtime::{self, Class, Object, Protocol, Sel},
             sel, sel_impl, Message,
         };
-        use $crate::cocoa::base::{id, nil, BOOL, NO};
+        use $crate::cocoa::base::id;
         use $crate::objc_foundation::INSObject;
-        use $crate::objc_id::{Id, ShareId};
-        use $crate::raw_nspanel::RawNSPanel;
-        use $crate::raw_overlaypanel::RawOverlayPanel;
-        use $crate::tauri::Runtime;
-        use $crate::block::ConcreteBlock;
-        use std::ffi::{c_void, c_char};
-
-        macro_rules! snake_to_camel {
-            ($input:ident) => {{
-                let mut camel_case = String::new();
-                let mut chars = $input.chars().peekable();
-                while let Some(c) = chars.next() {
-                    if c == '_' {
-                        if let Some(next) = chars.peek() {
-                            camel_case.push(next.to_ascii_uppercase());
-                            chars.next();
-                        }
-                    } else {
-                        camel_case.push(c);
-                    }
-                }
-                camel_case
-            }};
-        }
-
-        macro_rules! sel_from_func {
-            ($func:ident) => ({
-                let func_str = stringify!($func);
-                let sel_str = snake_to_camel!(func_str);
-                sel_impl!(format!("{}:{}", sel_str, '\0').as_str())
-            });
-        }
+        use $crate::PanelEvent;
+        use std::ffi::c_void;
 
         #[allow(dead_code)]
         const DELEGATE_CLS_NAME: &str = stringify!($delegate_name);
@@ -66,17 +36,39 @@ macro_rules! panel_delegate {
 
                 unsafe {
                     cls.add_ivar::<*mut c_void>("_listener");
+                    cls.add_ivar::<*mut c_void>("_subscribers");
+                    cls.add_ivar::<id>("_originalDelegate");
 
                     cls.add_method(
                         sel!(setListener:),
-                        Self::handle_set_listener as extern "C" fn(&mut Object, Sel, *mut c_void),
+                        $crate::delegate::set_listener as extern "C" fn(&mut Object, Sel, *mut c_void),
+                    );
+
+                    cls.add_method(
+                        sel!(setSubscribers:),
+                        $crate::delegate::set_subscribers as extern "C" fn(&mut Object, Sel, *mut c_void),
+                    );
+
+                    // Keep the window's previous delegate working, see `delegate::keep_original`
+                    cls.add_method(
+                        sel!(setOriginalDelegate:),
+                        $crate::delegate::set_original_delegate as extern "C" fn(&mut Object, Sel, id),
+                    );
+                    cls.add_method(
+                        sel!(originalDelegate),
+                        $crate::delegate::original_delegate as extern "C" fn(&Object, Sel) -> id,
+                    );
+                    cls.add_method(
+                        sel!(respondsToSelector:),
+                        $crate::delegate::responds_to_selector as extern "C" fn(&Object, Sel, Sel) -> runtime::BOOL,
+                    );
+                    cls.add_method(
+                        sel!(forwardingTargetForSelector:),
+                        $crate::delegate::forwarding_target_for_selector as extern "C" fn(&Object, Sel, Sel) -> id,
                     );
 
                     $(
-                        cls.add_method(
-                            sel_from_func!($fn_name),
-                            Self::$fn_name as extern "C" fn(&Object, Sel, id),
-                        );
+                        $crate::__panel_delegate_method!(cls, $fn_name $(= $selector)?);
                     )*
 
                     cls.add_method(
@@ -88,25 +80,9 @@ macro_rules! panel_delegate {
                 cls.register()
             }
 
-            extern "C" fn handle_set_listener(this: &mut Object, _: Sel, listener: *mut c_void) {
-                unsafe { this.set_ivar::<*mut c_void>("_listener", listener) };
-            }
-
-            $(
-                extern "C" fn $fn_name(this: &Object, _: Sel, _: id) {
-                    let delegate_name = std::ffi::CString::new(stringify!($fn_name)).unwrap();
-                    let delegate_name_ptr = delegate_name.as_ptr();
-
-                    let listener: *mut c_void = unsafe { *this.get_ivar("_listener") };
-                    let listener = listener as *const ConcreteBlock<(*const c_char, BOOL, *mut Object, *mut Sel, *mut c_void), (), ()>;
-
-                    unsafe {
-                        (*listener).call((delegate_name_ptr, NO, std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut()));
-                    }
-                }
-            )*
-
             extern "C" fn dealloc(this: &mut Object, _cmd: Sel) {
+                $crate::delegate::release(this);
+
                 unsafe {
                     let superclass = class!(NSObject);
                     let dealloc: extern "C" fn(&mut Object, Sel) =
@@ -125,16 +101,212 @@ macro_rules! panel_delegate {
         }
 
         impl $delegate_name {
-            pub fn set_listener(&self, callback: Box<dyn Fn(String)>) {
-                let block = ConcreteBlock::new(move |_delegate_name: *const c_char| {
-                    let delegate_name = unsafe { std::ffi::CStr::from_ptr(_delegate_name).to_string_lossy().into_owned() };
-                    callback(delegate_name);
-                });
-                let block = block.copy();
-                let _: () = unsafe { msg_send![self, setListener: block] };
+            /// Replaces the listener, the delegate owns it until it is deallocated.
+            ///
+            /// Use [`Panel::on_event`]($crate::Panel::on_event) to let several handlers receive
+            /// the events.
+            #[allow(dead_code)]
+            pub fn set_listener(&self, callback: Box<dyn Fn(PanelEvent)>) {
+                let listener = $crate::delegate::into_raw(callback);
+
+                let _: () = unsafe { msg_send![self, setListener: listener] };
             }
         }
 
         $delegate_name::new()
     }};
-}
\ No newline at end of file
+}
+
+/// Registers the implementation of a delegate method on the delegate class `$cls`.
+///
+/// The implementation is chosen by selector, so a method named after its selector and one
+/// given the selector explicitly behave the same. Methods returning a value get a dedicated
+/// implementation or fail to compile, every other method is treated as a notification.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __panel_delegate_method {
+    ($cls:ident, window_should_close) => {
+        $crate::__panel_delegate_method!($cls, window_should_close = "windowShouldClose:")
+    };
+    ($cls:ident, window_will_resize__to_size) => {
+        $crate::__panel_delegate_method!($cls, window_will_resize__to_size = "windowWillResize:toSize:")
+    };
+    ($cls:ident, window_will_use_standard_frame__default_frame) => {
+        $crate::__panel_delegate_method!(
+            $cls,
+            window_will_use_standard_frame__default_frame = "windowWillUseStandardFrame:defaultFrame:"
+        )
+    };
+    ($cls:ident, window_will_return_field_editor__to_object) => {
+        $crate::__panel_delegate_method!(
+            $cls,
+            window_will_return_field_editor__to_object = "windowWillReturnFieldEditor:toObject:"
+        )
+    };
+    ($cls:ident, window__will_position_sheet__using_rect) => {
+        $crate::__panel_delegate_method!(
+            $cls,
+            window__will_position_sheet__using_rect = "window:willPositionSheet:usingRect:"
+        )
+    };
+    ($cls:ident, window_should_zoom__to_frame) => {
+        $crate::__panel_delegate_method!($cls, window_should_zoom__to_frame = "windowShouldZoom:toFrame:")
+    };
+    ($cls:ident, window_will_return_undo_manager) => {
+        $crate::__panel_delegate_method!($cls, window_will_return_undo_manager = "windowWillReturnUndoManager:")
+    };
+    ($cls:ident, window__should_pop_up_document_path_menu) => {
+        $crate::__panel_delegate_method!($cls, window__should_pop_up_document_path_menu = "window:shouldPopUpDocumentPathMenu:")
+    };
+    ($cls:ident, window__should_drag_document_with_event__from__with_pasteboard) => {
+        $crate::__panel_delegate_method!($cls, window__should_drag_document_with_event__from__with_pasteboard = "window:shouldDragDocumentWithEvent:from:withPasteboard:")
+    };
+    ($cls:ident, window__will_use_full_screen_content_size) => {
+        $crate::__panel_delegate_method!($cls, window__will_use_full_screen_content_size = "window:willUseFullScreenContentSize:")
+    };
+    ($cls:ident, window__will_use_full_screen_presentation_options) => {
+        $crate::__panel_delegate_method!($cls, window__will_use_full_screen_presentation_options = "window:willUseFullScreenPresentationOptions:")
+    };
+    ($cls:ident, custom_windows_to_enter_full_screen_for_window) => {
+        $crate::__panel_delegate_method!($cls, custom_windows_to_enter_full_screen_for_window = "customWindowsToEnterFullScreenForWindow:")
+    };
+    ($cls:ident, custom_windows_to_enter_full_screen_for_window__on_screen) => {
+        $crate::__panel_delegate_method!($cls, custom_windows_to_enter_full_screen_for_window__on_screen = "customWindowsToEnterFullScreenForWindow:onScreen:")
+    };
+    ($cls:ident, custom_windows_to_exit_full_screen_for_window) => {
+        $crate::__panel_delegate_method!($cls, custom_windows_to_exit_full_screen_for_window = "customWindowsToExitFullScreenForWindow:")
+    };
+    ($cls:ident, window__will_resize_for_version_browser_with_max_preferred_size__max_allowed_size) => {
+        $crate::__panel_delegate_method!($cls, window__will_resize_for_version_browser_with_max_preferred_size__max_allowed_size = "window:willResizeForVersionBrowserWithMaxPreferredSize:maxAllowedSize:")
+    };
+    ($cls:ident, preview_representable_activity_items_for_window) => {
+        $crate::__panel_delegate_method!($cls, preview_representable_activity_items_for_window = "previewRepresentableActivityItemsForWindow:")
+    };
+    ($cls:ident, window_for_sharing_request_from_window) => {
+        $crate::__panel_delegate_method!($cls, window_for_sharing_request_from_window = "windowForSharingRequestFromWindow:")
+    };
+    ($cls:ident, $method:ident) => {
+        $crate::__panel_delegate_method!(@notify $cls, &$crate::delegate::selector(stringify!($method)))
+    };
+    ($cls:ident, $method:ident = "windowShouldClose:") => {
+        $cls.add_method(
+            $crate::objc::sel!(windowShouldClose:),
+            $crate::delegate::window_should_close
+                as extern "C" fn(
+                    &$crate::objc::runtime::Object,
+                    $crate::objc::runtime::Sel,
+                    $crate::cocoa::base::id,
+                ) -> $crate::cocoa::base::BOOL,
+        )
+    };
+    ($cls:ident, $method:ident = "windowWillResize:toSize:") => {
+        $cls.add_method(
+            $crate::objc::sel!(windowWillResize:toSize:),
+            $crate::delegate::window_will_resize_to_size
+                as extern "C" fn(
+                    &$crate::objc::runtime::Object,
+                    $crate::objc::runtime::Sel,
+                    $crate::cocoa::base::id,
+                    $crate::cocoa::foundation::NSSize,
+                ) -> $crate::cocoa::foundation::NSSize,
+        )
+    };
+    ($cls:ident, $method:ident = "windowWillUseStandardFrame:defaultFrame:") => {
+        $cls.add_method(
+            $crate::objc::sel!(windowWillUseStandardFrame:defaultFrame:),
+            $crate::delegate::window_will_use_standard_frame_default_frame
+                as extern "C" fn(
+                    &$crate::objc::runtime::Object,
+                    $crate::objc::runtime::Sel,
+                    $crate::cocoa::base::id,
+                    $crate::cocoa::foundation::NSRect,
+                ) -> $crate::cocoa::foundation::NSRect,
+        )
+    };
+    ($cls:ident, $method:ident = "windowWillReturnFieldEditor:toObject:") => {
+        $cls.add_method(
+            $crate::objc::sel!(windowWillReturnFieldEditor:toObject:),
+            $crate::delegate::window_will_return_field_editor_to_object
+                as extern "C" fn(
+                    &$crate::objc::runtime::Object,
+                    $crate::objc::runtime::Sel,
+                    $crate::cocoa::base::id,
+                    $crate::cocoa::base::id,
+                ) -> $crate::cocoa::base::id,
+        )
+    };
+    ($cls:ident, $method:ident = "window:willPositionSheet:usingRect:") => {
+        $cls.add_method(
+            $crate::objc::sel!(window:willPositionSheet:usingRect:),
+            $crate::delegate::window_will_position_sheet_using_rect
+                as extern "C" fn(
+                    &$crate::objc::runtime::Object,
+                    $crate::objc::runtime::Sel,
+                    $crate::cocoa::base::id,
+                    $crate::cocoa::base::id,
+                    $crate::cocoa::foundation::NSRect,
+                ) -> $crate::cocoa::foundation::NSRect,
+        )
+    };
+    ($cls:ident, $method:ident = "windowShouldZoom:toFrame:") => {
+        $crate::__panel_delegate_method!(@returns_value "windowShouldZoom:toFrame:")
+    };
+    ($cls:ident, $method:ident = "windowWillReturnUndoManager:") => {
+        $crate::__panel_delegate_method!(@returns_value "windowWillReturnUndoManager:")
+    };
+    ($cls:ident, $method:ident = "window:shouldPopUpDocumentPathMenu:") => {
+        $crate::__panel_delegate_method!(@returns_value "window:shouldPopUpDocumentPathMenu:")
+    };
+    ($cls:ident, $method:ident = "window:shouldDragDocumentWithEvent:from:withPasteboard:") => {
+        $crate::__panel_delegate_method!(@returns_value "window:shouldDragDocumentWithEvent:from:withPasteboard:")
+    };
+    ($cls:ident, $method:ident = "window:willUseFullScreenContentSize:") => {
+        $crate::__panel_delegate_method!(@returns_value "window:willUseFullScreenContentSize:")
+    };
+    ($cls:ident, $method:ident = "window:willUseFullScreenPresentationOptions:") => {
+        $crate::__panel_delegate_method!(@returns_value "window:willUseFullScreenPresentationOptions:")
+    };
+    ($cls:ident, $method:ident = "customWindowsToEnterFullScreenForWindow:") => {
+        $crate::__panel_delegate_method!(@returns_value "customWindowsToEnterFullScreenForWindow:")
+    };
+    ($cls:ident, $method:ident = "customWindowsToEnterFullScreenForWindow:onScreen:") => {
+        $crate::__panel_delegate_method!(@returns_value "customWindowsToEnterFullScreenForWindow:onScreen:")
+    };
+    ($cls:ident, $method:ident = "customWindowsToExitFullScreenForWindow:") => {
+        $crate::__panel_delegate_method!(@returns_value "customWindowsToExitFullScreenForWindow:")
+    };
+    ($cls:ident, $method:ident = "window:willResizeForVersionBrowserWithMaxPreferredSize:maxAllowedSize:") => {
+        $crate::__panel_delegate_method!(@returns_value "window:willResizeForVersionBrowserWithMaxPreferredSize:maxAllowedSize:")
+    };
+    ($cls:ident, $method:ident = "previewRepresentableActivityItemsForWindow:") => {
+        $crate::__panel_delegate_method!(@returns_value "previewRepresentableActivityItemsForWindow:")
+    };
+    ($cls:ident, $method:ident = "windowForSharingRequestFromWindow:") => {
+        $crate::__panel_delegate_method!(@returns_value "windowForSharingRequestFromWindow:")
+    };
+    ($cls:ident, $method:ident = $selector:literal) => {
+        $crate::__panel_delegate_method!(@notify $cls, $selector)
+    };
+    (@notify $cls:ident, $selector:expr) => {
+        $cls.add_method(
+            $crate::objc::runtime::Sel::register($selector),
+            $crate::delegate::notify
+                as extern "C" fn(
+                    &$crate::objc::runtime::Object,
+                    $crate::objc::runtime::Sel,
+                    $crate::cocoa::base::id,
+                ),
+        )
+    };
+    // Treated as a notification, AppKit would read garbage as the method's answer
+    (@returns_value $selector:literal) => {
+        compile_error!(concat!(
+            "panel_delegate! does not support `",
+            $selector,
+            "`: of the delegate methods returning a value, only `windowShouldClose:`, ",
+            "`windowWillResize:toSize:`, `windowWillUseStandardFrame:defaultFrame:`, ",
+            "`window:willPositionSheet:usingRect:` and `windowWillReturnFieldEditor:toObject:` ",
+            "are supported"
+        ))
+    };
+}
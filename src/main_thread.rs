@@ -0,0 +1,404 @@
+#[cfg(target_os = "macos")]
+use std::mem::ManuallyDrop;
+use std::{
+    sync::{mpsc, Arc},
+    thread::{self, ThreadId},
+    time::Duration,
+};
+
+#[cfg(target_os = "macos")]
+use cocoa::base::{id, BOOL, YES};
+#[cfg(target_os = "macos")]
+use objc::{class, msg_send, runtime::Object, sel, sel_impl};
+#[cfg(target_os = "macos")]
+use objc_id::ShareId;
+use tauri::{AppHandle, Runtime};
+
+use crate::{
+    backend::PanelBackend, CollectionBehavior, Easing, Error, PanelLevel, PanelStyleMask, Point,
+    Rect, Screen,
+};
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Runs closures on the thread driving the app's event loop.
+#[derive(Clone)]
+pub(crate) enum MainThread {
+    /// Runs closures on the calling thread, for panels created without an app.
+    Inline,
+    App {
+        id: ThreadId,
+        run: Arc<dyn Fn(Task) -> tauri::Result<()> + Send + Sync>,
+    },
+}
+
+impl MainThread {
+    /// Must be called on the main thread, which the plugin's setup hook runs on.
+    pub(crate) fn new<R: Runtime>(app: AppHandle<R>) -> Self {
+        Self::App {
+            id: thread::current().id(),
+            run: Arc::new(move |task| app.run_on_main_thread(task)),
+        }
+    }
+
+    fn is_current(&self) -> bool {
+        match self {
+            Self::Inline => true,
+            Self::App { id, .. } => thread::current().id() == *id,
+        }
+    }
+
+    /// Runs `f` on the main thread and waits for its result.
+    pub(crate) fn run<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        match self {
+            Self::App { run, .. } if !self.is_current() => {
+                let (tx, rx) = mpsc::sync_channel(1);
+                run(Box::new(move || {
+                    let _ = tx.send(f());
+                }))
+                .map_err(|_| Error::MainThreadUnavailable)?;
+
+                // The sender is dropped without a value if the task is dropped or panics
+                rx.recv().map_err(|_| Error::MainThreadUnavailable)
+            }
+            _ => Ok(f()),
+        }
+    }
+
+    /// Queues `f` to run on the main thread without waiting for it, or runs it right away
+    /// when already on the main thread.
+    pub(crate) fn spawn<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        match self {
+            Self::App { run, .. } if !self.is_current() => {
+                run(Box::new(f)).map_err(|_| Error::MainThreadUnavailable)
+            }
+            _ => {
+                f();
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A pointer that is only ever dereferenced on the main thread.
+#[cfg(target_os = "macos")]
+pub(crate) struct MainThreadPtr(pub(crate) id);
+
+#[cfg(target_os = "macos")]
+unsafe impl Send for MainThreadPtr {}
+
+#[cfg(target_os = "macos")]
+impl MainThreadPtr {
+    /// Closures must call this rather than read the field, so they capture the whole `Send`
+    /// wrapper.
+    pub(crate) fn get(self) -> id {
+        self.0
+    }
+}
+
+/// An object that is only ever used and released on the main thread.
+///
+/// The closure holding it is dropped on the calling thread when the main thread no longer
+/// accepts work. The object is leaked then rather than released off the main thread, which
+/// only happens while the app is shutting down.
+#[cfg(target_os = "macos")]
+pub(crate) struct MainThreadObject(ManuallyDrop<ShareId<Object>>);
+
+#[cfg(target_os = "macos")]
+unsafe impl Send for MainThreadObject {}
+
+#[cfg(target_os = "macos")]
+impl MainThreadObject {
+    pub(crate) fn new(object: ShareId<Object>) -> Self {
+        Self(ManuallyDrop::new(object))
+    }
+
+    /// Closures must call this rather than read the field, so they capture the whole wrapper
+    /// and the object goes wherever the closure is dropped.
+    pub(crate) fn get(&self) -> id {
+        &**self.0 as *const Object as id
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for MainThreadObject {
+    fn drop(&mut self) {
+        let is_main_thread: BOOL = unsafe { msg_send![class!(NSThread), isMainThread] };
+
+        if is_main_thread == YES {
+            // SAFETY: the object is never used again
+            unsafe { ManuallyDrop::drop(&mut self.0) }
+        }
+    }
+}
+
+/// Forwards every call to the main thread before reaching the backend.
+///
+/// The backend's methods cannot fail, so when the main thread no longer accepts work, which
+/// only happens while the app is shutting down, the call is logged and skipped and getters
+/// return the default value of their result. Callers that must not act on such a value go
+/// through [`MainThread::run`], see [`Panel::run_on_main_thread`](crate::Panel::run_on_main_thread).
+pub(crate) struct MainThreadPanel {
+    pub(crate) backend: Arc<dyn PanelBackend>,
+    pub(crate) main_thread: MainThread,
+}
+
+impl MainThreadPanel {
+    fn run<T, F>(&self, f: F) -> T
+    where
+        T: Default + Send + 'static,
+        F: FnOnce(&dyn PanelBackend) -> T + Send + 'static,
+    {
+        let backend = self.backend.clone();
+        self.main_thread
+            .run(move || f(&*backend))
+            .unwrap_or_else(|e| {
+                log::warn!("skipped a panel call: {e}");
+                T::default()
+            })
+    }
+}
+
+impl PanelBackend for MainThreadPanel {
+    fn show(&self) {
+        self.run(|panel| panel.show())
+    }
+
+    fn is_visible(&self) -> bool {
+        self.run(|panel| panel.is_visible())
+    }
+
+    fn set_can_become_key_window(&self, flag: bool) {
+        self.run(move |panel| panel.set_can_become_key_window(flag))
+    }
+
+    fn set_can_become_main_window(&self, flag: bool) {
+        self.run(move |panel| panel.set_can_become_main_window(flag))
+    }
+
+    fn make_key_window(&self) {
+        self.run(|panel| panel.make_key_window())
+    }
+
+    fn resign_key_window(&self) {
+        self.run(|panel| panel.resign_key_window())
+    }
+
+    fn order_front_regardless(&self) {
+        self.run(|panel| panel.order_front_regardless())
+    }
+
+    fn order_front(&self) {
+        self.run(|panel| panel.order_front())
+    }
+
+    fn order_out(&self) {
+        self.run(|panel| panel.order_out())
+    }
+
+    fn set_level(&self, level: PanelLevel) {
+        self.run(move |panel| panel.set_level(level))
+    }
+
+    fn level(&self) -> PanelLevel {
+        self.run(|panel| panel.level())
+    }
+
+    fn set_alpha_value(&self, value: f64) {
+        self.run(move |panel| panel.set_alpha_value(value))
+    }
+
+    fn alpha_value(&self) -> f64 {
+        self.run(|panel| panel.alpha_value())
+    }
+
+    fn set_content_size(&self, width: f64, height: f64) {
+        self.run(move |panel| panel.set_content_size(width, height))
+    }
+
+    fn set_frame(&self, frame: Rect, animate: bool) {
+        self.run(move |panel| panel.set_frame(frame, animate))
+    }
+
+    fn frame(&self) -> Rect {
+        self.run(|panel| panel.frame())
+    }
+
+    fn animate(
+        &self,
+        frame: Rect,
+        alpha: f64,
+        duration: Duration,
+        easing: Easing,
+        completion: Box<dyn FnOnce() + Send>,
+    ) {
+        self.run(move |panel| panel.animate(frame, alpha, duration, easing, completion))
+    }
+
+    fn screen(&self) -> Option<Screen> {
+        self.run(|panel| panel.screen())
+    }
+
+    fn screens(&self) -> Vec<Screen> {
+        self.run(|panel| panel.screens())
+    }
+
+    fn mouse_location(&self) -> Point {
+        self.run(|panel| panel.mouse_location())
+    }
+
+    fn key_window_screen(&self) -> Option<Screen> {
+        self.run(|panel| panel.key_window_screen())
+    }
+
+    fn set_style_mask(&self, style_mask: PanelStyleMask) {
+        self.run(move |panel| panel.set_style_mask(style_mask))
+    }
+
+    fn style_mask(&self) -> PanelStyleMask {
+        self.run(|panel| panel.style_mask())
+    }
+
+    fn set_collection_behaviour(&self, behaviour: CollectionBehavior) {
+        self.run(move |panel| panel.set_collection_behaviour(behaviour))
+    }
+
+    fn set_floating_panel(&self, flag: bool) {
+        self.run(move |panel| panel.set_floating_panel(flag))
+    }
+
+    fn set_accepts_mouse_moved_events(&self, flag: bool) {
+        self.run(move |panel| panel.set_accepts_mouse_moved_events(flag))
+    }
+
+    fn set_ignores_mouse_events(&self, flag: bool) {
+        self.run(move |panel| panel.set_ignores_mouse_events(flag))
+    }
+
+    fn set_hides_on_deactivate(&self, flag: bool) {
+        self.run(move |panel| panel.set_hides_on_deactivate(flag))
+    }
+
+    fn set_movable_by_window_background(&self, flag: bool) {
+        self.run(move |panel| panel.set_movable_by_window_background(flag))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn set_delegate(&self, delegate: id) {
+        let delegate = MainThreadPtr(delegate);
+        self.run(move |panel| panel.set_delegate(delegate.get()))
+    }
+
+    fn released_when_closed(&self, flag: bool) {
+        self.run(move |panel| panel.released_when_closed(flag))
+    }
+
+    fn close(&self) {
+        self.run(|panel| panel.close())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{memory_panel::MemoryPanel, Panel, Standard};
+
+    /// A main thread whose tasks are queued until the test runs them.
+    fn queued() -> (MainThread, mpsc::Receiver<Task>) {
+        let (tx, rx) = mpsc::channel::<Task>();
+        let tx = Mutex::new(tx);
+
+        let main_thread = MainThread::App {
+            id: thread::current().id(),
+            run: Arc::new(move |task| {
+                tx.lock()
+                    .unwrap()
+                    .send(task)
+                    .map_err(|_| tauri::Error::FailedToReceiveMessage)
+            }),
+        };
+
+        (main_thread, rx)
+    }
+
+    #[test]
+    fn runs_inline_on_the_main_thread() {
+        let (main_thread, tasks) = queued();
+
+        assert_eq!(main_thread.run(|| 1).unwrap(), 1);
+        assert!(tasks.try_recv().is_err());
+    }
+
+    #[test]
+    fn calls_from_other_threads_run_on_the_main_thread() {
+        let (main_thread, tasks) = queued();
+        let main_id = thread::current().id();
+        let panel = MainThreadPanel {
+            backend: Arc::new(MemoryPanel::default()),
+            main_thread: main_thread.clone(),
+        };
+
+        let worker = thread::spawn(move || {
+            panel.show();
+            let visible = panel.is_visible();
+            let ran_on = main_thread.run(|| thread::current().id()).unwrap();
+            (visible, ran_on)
+        });
+
+        for task in tasks.iter().take(3) {
+            task();
+        }
+
+        assert_eq!(worker.join().unwrap(), (true, main_id));
+    }
+
+    #[test]
+    fn spawned_tasks_do_not_wait() {
+        let (main_thread, tasks) = queued();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || main_thread.spawn(move || tx.send(()).unwrap()))
+            .join()
+            .unwrap()
+            .unwrap();
+
+        assert!(rx.try_recv().is_err());
+        tasks.recv().unwrap()();
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn dropped_tasks_are_reported() {
+        let (main_thread, tasks) = queued();
+        drop(tasks);
+
+        let result = thread::spawn(move || main_thread.run(|| ()))
+            .join()
+            .unwrap();
+
+        assert_eq!(result, Err(Error::MainThreadUnavailable));
+    }
+
+    #[test]
+    fn panels_report_calls_that_cannot_reach_the_main_thread() {
+        let (main_thread, tasks) = queued();
+        drop(tasks);
+        let panel =
+            Panel::on_main_thread::<Standard>(Arc::new(MemoryPanel::default()), main_thread);
+
+        let result = thread::spawn(move || panel.run_on_main_thread(|panel| panel.is_visible()))
+            .join()
+            .unwrap();
+
+        assert_eq!(result, Err(Error::MainThreadUnavailable));
+    }
+}
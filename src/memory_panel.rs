@@ -0,0 +1,262 @@
+use std::{sync::Mutex, time::Duration};
+
+#[cfg(target_os = "macos")]
+use cocoa::base::id;
+use tauri::{Runtime, WebviewWindow};
+
+use crate::{
+    backend::PanelBackend, kind::PanelKind, CollectionBehavior, Easing, PanelLevel, PanelStyleMask,
+    Point, Rect, Screen,
+};
+
+/// Snapshot of everything a [`MemoryPanel`] has been told.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryPanelState {
+    pub visible: bool,
+    pub key: bool,
+    pub can_become_key: bool,
+    pub can_become_main: bool,
+    pub level: PanelLevel,
+    pub alpha: f64,
+    pub content_size: Option<(f64, f64)>,
+    pub frame: Rect,
+    /// The connected screens, the one holding the menu bar first.
+    pub screens: Vec<Screen>,
+    pub mouse_location: Point,
+    /// The [`Screen::id`] of the app's key window's screen.
+    pub key_window_screen: Option<u32>,
+    pub style_mask: PanelStyleMask,
+    pub collection_behaviour: CollectionBehavior,
+    pub floating: bool,
+    pub accepts_mouse_moved_events: bool,
+    pub ignores_mouse_events: bool,
+    pub hides_on_deactivate: bool,
+    pub movable_by_window_background: bool,
+    pub released_when_closed: bool,
+    pub closed: bool,
+}
+
+impl Default for MemoryPanelState {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            key: false,
+            can_become_key: true,
+            can_become_main: true,
+            level: PanelLevel::Normal,
+            alpha: 1.0,
+            content_size: None,
+            frame: Rect::default(),
+            screens: Vec::new(),
+            mouse_location: Point::default(),
+            key_window_screen: None,
+            style_mask: PanelStyleMask::BORDERLESS,
+            collection_behaviour: CollectionBehavior::empty(),
+            floating: false,
+            accepts_mouse_moved_events: false,
+            ignores_mouse_events: false,
+            hides_on_deactivate: true,
+            movable_by_window_background: false,
+            released_when_closed: false,
+            closed: false,
+        }
+    }
+}
+
+/// A panel that only lives in memory.
+///
+/// This is the backend used on platforms without AppKit, it mirrors what the native panel
+/// would do closely enough to test code that drives panels.
+#[derive(Debug, Default)]
+pub struct MemoryPanel {
+    state: Mutex<MemoryPanelState>,
+}
+
+impl MemoryPanel {
+    pub fn new(state: MemoryPanelState) -> Self {
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Create a memory panel standing in for a Tauri Webview Window converted to kind `K`
+    pub fn from_window<K: PanelKind, R: Runtime>(window: WebviewWindow<R>) -> Self {
+        Self::new(MemoryPanelState {
+            visible: window.is_visible().unwrap_or_default(),
+            can_become_key: K::CAN_BECOME_KEY_WINDOW,
+            can_become_main: K::CAN_BECOME_MAIN_WINDOW.unwrap_or(true),
+            ..Default::default()
+        })
+    }
+
+    /// Replaces the connected screens, the one holding the menu bar first.
+    pub fn set_screens(&self, screens: Vec<Screen>) {
+        self.lock().screens = screens;
+    }
+
+    /// Changes the state the way the rest of the system would, such as moving the mouse.
+    pub fn update(&self, f: impl FnOnce(&mut MemoryPanelState)) {
+        f(&mut self.lock());
+    }
+
+    pub fn state(&self) -> MemoryPanelState {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryPanelState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl PanelBackend for MemoryPanel {
+    fn show(&self) {
+        self.order_front_regardless();
+        self.make_key_window();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.lock().visible
+    }
+
+    fn set_can_become_key_window(&self, flag: bool) {
+        self.lock().can_become_key = flag;
+    }
+
+    fn set_can_become_main_window(&self, flag: bool) {
+        self.lock().can_become_main = flag;
+    }
+
+    fn make_key_window(&self) {
+        let mut state = self.lock();
+        state.key = state.can_become_key;
+    }
+
+    fn resign_key_window(&self) {
+        self.lock().key = false;
+    }
+
+    fn order_front_regardless(&self) {
+        self.lock().visible = true;
+    }
+
+    fn order_front(&self) {
+        self.lock().visible = true;
+    }
+
+    fn order_out(&self) {
+        let mut state = self.lock();
+        state.visible = false;
+        state.key = false;
+    }
+
+    fn set_level(&self, level: PanelLevel) {
+        self.lock().level = level;
+    }
+
+    fn level(&self) -> PanelLevel {
+        self.lock().level.clone()
+    }
+
+    fn set_alpha_value(&self, value: f64) {
+        self.lock().alpha = value;
+    }
+
+    fn alpha_value(&self) -> f64 {
+        self.lock().alpha
+    }
+
+    fn set_content_size(&self, width: f64, height: f64) {
+        self.lock().content_size = Some((width, height));
+    }
+
+    fn set_frame(&self, frame: Rect, _animate: bool) {
+        self.lock().frame = frame;
+    }
+
+    fn frame(&self) -> Rect {
+        self.lock().frame
+    }
+
+    /// Jumps to the end of the animation.
+    fn animate(
+        &self,
+        frame: Rect,
+        alpha: f64,
+        _duration: Duration,
+        _easing: Easing,
+        completion: Box<dyn FnOnce() + Send>,
+    ) {
+        {
+            let mut state = self.lock();
+            state.frame = frame;
+            state.alpha = alpha;
+        }
+
+        completion();
+    }
+
+    fn screen(&self) -> Option<Screen> {
+        let state = self.lock();
+        Screen::for_frame(&state.frame, &state.screens)
+    }
+
+    fn screens(&self) -> Vec<Screen> {
+        self.lock().screens.clone()
+    }
+
+    fn mouse_location(&self) -> Point {
+        self.lock().mouse_location
+    }
+
+    fn key_window_screen(&self) -> Option<Screen> {
+        let state = self.lock();
+        let id = state.key_window_screen?;
+        state.screens.iter().find(|screen| screen.id == id).copied()
+    }
+
+    fn set_style_mask(&self, style_mask: PanelStyleMask) {
+        self.lock().style_mask = style_mask;
+    }
+
+    fn style_mask(&self) -> PanelStyleMask {
+        self.lock().style_mask
+    }
+
+    fn set_collection_behaviour(&self, behaviour: CollectionBehavior) {
+        self.lock().collection_behaviour = behaviour;
+    }
+
+    fn set_floating_panel(&self, flag: bool) {
+        self.lock().floating = flag;
+    }
+
+    fn set_accepts_mouse_moved_events(&self, flag: bool) {
+        self.lock().accepts_mouse_moved_events = flag;
+    }
+
+    fn set_ignores_mouse_events(&self, flag: bool) {
+        self.lock().ignores_mouse_events = flag;
+    }
+
+    fn set_hides_on_deactivate(&self, flag: bool) {
+        self.lock().hides_on_deactivate = flag;
+    }
+
+    fn set_movable_by_window_background(&self, flag: bool) {
+        self.lock().movable_by_window_background = flag;
+    }
+
+    #[cfg(target_os = "macos")]
+    fn set_delegate(&self, _delegate: id) {}
+
+    fn released_when_closed(&self, flag: bool) {
+        self.lock().released_when_closed = flag;
+    }
+
+    fn close(&self) {
+        let mut state = self.lock();
+        state.visible = false;
+        state.key = false;
+        state.closed = true;
+    }
+}
@@ -0,0 +1,591 @@
+#[cfg(target_os = "macos")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{fmt, ops::Deref, sync::Arc};
+
+#[cfg(target_os = "macos")]
+use objc::{runtime::Object, Message};
+#[cfg(target_os = "macos")]
+use objc_id::{Id, ShareId};
+
+#[cfg(target_os = "macos")]
+use crate::main_thread::MainThreadObject;
+
+use crate::{
+    animation::{self, Animation, Animations},
+    backend::PanelBackend,
+    coordinates,
+    event::{Subscribers, SubscriptionId},
+    kind::PanelKind,
+    main_thread::{MainThread, MainThreadPanel},
+    placement::{self, Placement, PlacementOptions},
+    show_target::{self, ShowTarget},
+    Error, PanelEvent, PanelStyleMask, Rect, Screen,
+};
+
+/// A cheaply clonable handle to a panel of any [`PanelKind`].
+///
+/// Every method of [`PanelBackend`] is reachable through this handle, whichever backend
+/// drives the panel. Panels created by the plugin can be used from any thread: calls made
+/// off the main thread are run on it and wait for their result.
+///
+/// Those methods cannot fail. Should the main thread stop accepting work while the app shuts
+/// down, they are logged and skipped, use [`Panel::run_on_main_thread`] to learn about it.
+#[derive(Clone)]
+pub struct Panel {
+    panel: Arc<MainThreadPanel>,
+    kind: &'static str,
+    subscribers: Subscribers,
+    animations: Animations,
+    /// Whether the panel's delegate hands its events to the subscribers.
+    #[cfg(target_os = "macos")]
+    delivers_events: Arc<AtomicBool>,
+}
+
+impl Panel {
+    pub fn new<K: PanelKind>(backend: impl PanelBackend + 'static) -> Self {
+        Self::from_backend::<K>(Arc::new(backend))
+    }
+
+    /// Wraps a backend, calls reach it on the calling thread.
+    pub fn from_backend<K: PanelKind>(backend: Arc<dyn PanelBackend>) -> Self {
+        Self::on_main_thread::<K>(backend, MainThread::Inline)
+    }
+
+    pub(crate) fn on_main_thread<K: PanelKind>(
+        backend: Arc<dyn PanelBackend>,
+        main_thread: MainThread,
+    ) -> Self {
+        Self {
+            panel: Arc::new(MainThreadPanel {
+                backend,
+                main_thread,
+            }),
+            kind: K::CLASS_NAME,
+            subscribers: Subscribers::default(),
+            animations: Animations::default(),
+            #[cfg(target_os = "macos")]
+            delivers_events: Arc::default(),
+        }
+    }
+
+    /// The [`PanelKind::CLASS_NAME`] of this panel's kind.
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    /// Returns `true` if the panel is of kind `K`.
+    pub fn is<K: PanelKind>(&self) -> bool {
+        self.kind == K::CLASS_NAME
+    }
+
+    /// Returns the handle if the panel is of kind `K`, fails with [`Error::WrongPanelKind`]
+    /// otherwise.
+    pub(crate) fn of_kind<K: PanelKind>(self) -> Result<Self, Error> {
+        if self.is::<K>() {
+            Ok(self)
+        } else {
+            Err(Error::WrongPanelKind {
+                expected: K::CLASS_NAME,
+                found: self.kind,
+            })
+        }
+    }
+
+    /// Returns `true` if both handles point to the same panel.
+    pub fn ptr_eq(&self, other: &Panel) -> bool {
+        Arc::ptr_eq(&self.panel.backend, &other.panel.backend)
+    }
+
+    /// Runs `f` with the panel on the main thread and waits for its result.
+    ///
+    /// Use it to make several calls in one trip to the main thread. `f` runs right away when
+    /// called on the main thread, so never call this from a thread the main thread waits on.
+    pub fn run_on_main_thread<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn PanelBackend) -> T + Send + 'static,
+    {
+        let backend = self.panel.backend.clone();
+        self.panel.main_thread.run(move || f(&*backend))
+    }
+
+    /// Queues `f` to run with the panel on the main thread without waiting for it.
+    ///
+    /// `f` runs right away when called on the main thread.
+    pub fn dispatch<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&dyn PanelBackend) + Send + 'static,
+    {
+        let backend = self.panel.backend.clone();
+        self.panel.main_thread.spawn(move || f(&*backend))
+    }
+
+    /// Sets the panel's style mask, rejecting combinations AppKit does not support.
+    pub fn set_style_mask(&self, style_mask: PanelStyleMask) -> Result<(), Error> {
+        self.panel.set_style_mask(style_mask.validate()?);
+        Ok(())
+    }
+
+    /// Moves the panel's top left corner to `position`, given in Tauri's coordinates like
+    /// [`WebviewWindow::set_position`](tauri::WebviewWindow::set_position).
+    ///
+    /// Physical positions are scaled with the factor of the panel's screen. Fails with
+    /// [`Error::ScreenUnavailable`] when no screen is connected.
+    pub fn set_position(&self, position: impl Into<tauri::Position>) -> Result<(), Error> {
+        let position = position.into();
+
+        self.run_on_main_thread(move |panel| {
+            let (primary_height, scale_factor) = screen_metrics(panel)?;
+            let size = panel.frame().size;
+            let origin = coordinates::to_cocoa_origin(position, size, primary_height, scale_factor);
+
+            panel.set_frame(Rect { origin, size }, false);
+            Ok(())
+        })?
+    }
+
+    /// The position of the panel's top left corner in Tauri's coordinates, in physical pixels
+    /// like [`WebviewWindow::outer_position`](tauri::WebviewWindow::outer_position).
+    ///
+    /// Fails with [`Error::ScreenUnavailable`] when no screen is connected.
+    pub fn position(&self) -> Result<tauri::PhysicalPosition<i32>, Error> {
+        self.run_on_main_thread(|panel| {
+            let (primary_height, scale_factor) = screen_metrics(panel)?;
+            let position = coordinates::to_tauri_position(panel.frame(), primary_height);
+
+            Ok(position.to_physical(scale_factor))
+        })?
+    }
+
+    /// The size of the panel's frame in physical pixels, like
+    /// [`WebviewWindow::outer_size`](tauri::WebviewWindow::outer_size).
+    ///
+    /// Fails with [`Error::ScreenUnavailable`] when no screen is connected.
+    pub fn size(&self) -> Result<tauri::PhysicalSize<u32>, Error> {
+        self.run_on_main_thread(|panel| {
+            let (_, scale_factor) = screen_metrics(panel)?;
+            let size = panel.frame().size;
+
+            Ok(tauri::LogicalSize::new(size.width, size.height).to_physical(scale_factor))
+        })?
+    }
+
+    /// Resizes the panel's frame to `size`, its top left corner stays in place.
+    ///
+    /// Physical sizes are scaled with the factor of the panel's screen. Fails with
+    /// [`Error::ScreenUnavailable`] when no screen is connected.
+    pub fn set_size(&self, size: impl Into<tauri::Size>) -> Result<(), Error> {
+        let size = size.into();
+
+        self.run_on_main_thread(move |panel| {
+            let (_, scale_factor) = screen_metrics(panel)?;
+            let size = coordinates::to_cocoa_size(size, scale_factor);
+
+            panel.set_frame(
+                coordinates::resize_from_top_left(panel.frame(), size),
+                false,
+            );
+            Ok(())
+        })?
+    }
+
+    /// Moves the panel next to `anchor`, a rectangle in AppKit screen coordinates, and returns
+    /// where it went.
+    ///
+    /// The panel keeps its size. It is flipped to the opposite edge when there is no room on
+    /// the preferred one, and kept inside the visible frame of the anchor's screen, see
+    /// [`placement::place`].
+    pub fn position_relative_to(
+        &self,
+        anchor: Rect,
+        options: PlacementOptions,
+    ) -> Result<Placement, Error> {
+        self.run_on_main_thread(move |panel| {
+            let screens: Vec<_> = panel
+                .screens()
+                .iter()
+                .map(|screen| screen.visible_frame)
+                .collect();
+            let placement = placement::place(panel.frame().size, anchor, &options, &screens);
+
+            panel.set_frame(placement.frame, false);
+            placement
+        })
+    }
+
+    /// Moves the panel to the screen `target` resolves to, then shows it like
+    /// [`PanelBackend::show`].
+    ///
+    /// The panel keeps its place relative to the visible frame of the screen, so a centered
+    /// panel is centered on the target screen too. It is shown where it is when it already is
+    /// on that screen or when the target screen is not connected.
+    pub fn show_on(&self, target: ShowTarget) -> Result<(), Error> {
+        self.run_on_main_thread(move |panel| {
+            let screens = panel.screens();
+
+            if let Some(to) = target.resolve(panel, &screens) {
+                let frame = panel.frame();
+                let from = panel
+                    .screen()
+                    .or_else(|| Screen::for_frame(&frame, &screens))
+                    .unwrap_or(to);
+
+                if from.id != to.id {
+                    panel.set_frame(
+                        show_target::relocated(frame, &from.visible_frame, &to.visible_frame),
+                        false,
+                    );
+                }
+            }
+
+            panel.show();
+        })
+    }
+
+    /// Shows the panel like [`PanelBackend::show`] with `animation`, then calls `on_done` on
+    /// the main thread.
+    ///
+    /// A hidden panel starts from where the transition hides it, a visible one from where it
+    /// is. `on_done` gets `false` when a later [`show_animated`](Panel::show_animated) or
+    /// [`hide_animated`](Panel::hide_animated) interrupted the transition, the panel then ends
+    /// where the later one leaves it.
+    pub fn show_animated<F>(&self, animation: Animation, on_done: F) -> Result<(), Error>
+    where
+        F: FnOnce(bool) + Send + 'static,
+    {
+        let backend = self.panel.backend.clone();
+        let animations = self.animations.clone();
+
+        self.panel.main_thread.run(move || {
+            let (generation, resting) = animations.begin(&*backend);
+
+            if !backend.is_visible() {
+                let screen = Screen::for_frame(&resting.frame, &backend.screens());
+                backend.set_frame(
+                    animation::hidden_frame(
+                        animation.transition,
+                        resting.frame,
+                        screen.map(|screen| screen.visible_frame),
+                    ),
+                    false,
+                );
+                backend.set_alpha_value(0.0);
+                backend.show();
+            }
+
+            backend.clone().animate(
+                resting.frame,
+                resting.alpha,
+                animation.duration,
+                animation.easing,
+                Box::new(move || {
+                    let finished = animations.end(generation).is_some();
+
+                    if finished {
+                        // Land exactly at rest, whatever the animation reached
+                        backend.set_frame(resting.frame, false);
+                        backend.set_alpha_value(resting.alpha);
+                    }
+
+                    on_done(finished);
+                }),
+            );
+        })
+    }
+
+    /// Hides the panel like [`PanelBackend::order_out`] with `animation`, then calls `on_done`
+    /// on the main thread.
+    ///
+    /// Once hidden, the panel is put back at its frame and alpha, so a later
+    /// [`show`](PanelBackend::show) brings it back as it was. `on_done` gets `false` when a
+    /// later transition interrupted this one, the panel then is not ordered out.
+    pub fn hide_animated<F>(&self, animation: Animation, on_done: F) -> Result<(), Error>
+    where
+        F: FnOnce(bool) + Send + 'static,
+    {
+        let backend = self.panel.backend.clone();
+        let animations = self.animations.clone();
+
+        self.panel.main_thread.run(move || {
+            let (generation, resting) = animations.begin(&*backend);
+
+            if !backend.is_visible() {
+                animations.end(generation);
+                on_done(true);
+                return;
+            }
+
+            let screen = Screen::for_frame(&resting.frame, &backend.screens());
+            let hidden = animation::hidden_frame(
+                animation.transition,
+                resting.frame,
+                screen.map(|screen| screen.visible_frame),
+            );
+
+            backend.clone().animate(
+                hidden,
+                0.0,
+                animation.duration,
+                animation.easing,
+                Box::new(move || {
+                    let finished = animations.end(generation).is_some();
+
+                    if finished {
+                        backend.order_out();
+                        backend.set_frame(resting.frame, false);
+                        backend.set_alpha_value(resting.alpha);
+                    }
+
+                    on_done(finished);
+                }),
+            );
+        })
+    }
+
+    /// Whether a [`show_animated`](Panel::show_animated) or
+    /// [`hide_animated`](Panel::hide_animated) transition is running.
+    pub(crate) fn is_animating(&self) -> bool {
+        self.animations.is_running()
+    }
+
+    /// Calls `handler` with every event of the panel's delegate, after the handlers subscribed
+    /// before it.
+    ///
+    /// Events only reach subscribers once a [`panel_delegate!`](crate::panel_delegate) delegate
+    /// is set on the panel, or once its events are
+    /// [forwarded](crate::ManagerExt::forward_panel_events). Handlers run on the main thread.
+    pub fn on_event<F>(&self, handler: F) -> SubscriptionId
+    where
+        F: Fn(PanelEvent) + Send + 'static,
+    {
+        self.subscribers.subscribe(Box::new(handler))
+    }
+
+    /// Unsubscribes a handler registered with [`Panel::on_event`], returns `false` if it was
+    /// already unsubscribed.
+    pub fn off(&self, id: SubscriptionId) -> bool {
+        self.subscribers.unsubscribe(id)
+    }
+
+    /// Hands `event` to the panel's subscribers.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn emit(&self, event: &PanelEvent) {
+        self.subscribers.emit(event);
+    }
+
+    /// Set the panel's delegate, usually one created with [`panel_delegate!`](crate::panel_delegate).
+    ///
+    /// The delegate's events are also delivered to the panel's [subscribers](Panel::on_event).
+    /// The panel keeps the delegate alive until its window is deallocated or given another
+    /// delegate.
+    #[cfg(target_os = "macos")]
+    pub fn set_delegate<T: Message>(&self, delegate: Id<T>) {
+        self.set_delegate_object(delegate_object(delegate));
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(crate) fn set_delegate_object(&self, delegate: ShareId<Object>) {
+        // Released on the main thread once the window has retained it
+        let delegate = MainThreadObject::new(delegate);
+        let subscribers = self.subscribers.clone();
+        let delivers_events = self.delivers_events.clone();
+
+        let _ = self.run_on_main_thread(move |panel| {
+            let shared = crate::delegate::share_subscribers(delegate.get(), subscribers);
+            delivers_events.store(shared, Ordering::Relaxed);
+            panel.set_delegate(delegate.get());
+        });
+    }
+
+    /// Gives the panel a delegate that only delivers its events to the subscribers, unless its
+    /// delegate already does.
+    #[cfg(target_os = "macos")]
+    pub(crate) fn deliver_events(&self) {
+        if !self.delivers_events.load(Ordering::Relaxed) {
+            self.set_delegate_object(crate::delegate::event_delegate());
+        }
+    }
+}
+
+/// The height of the primary screen and the scale factor of the panel's screen.
+fn screen_metrics(panel: &dyn PanelBackend) -> Result<(f64, f64), Error> {
+    let screens = panel.screens();
+    let primary = screens.first().ok_or(Error::ScreenUnavailable)?;
+    let screen = panel.screen().unwrap_or(*primary);
+
+    Ok((primary.frame.size.height, screen.scale_factor))
+}
+
+/// Shares ownership of a delegate of any class, until it is handed to a window.
+#[cfg(target_os = "macos")]
+pub(crate) fn delegate_object<T: Message>(delegate: Id<T>) -> ShareId<Object> {
+    // Retains the delegate, `delegate` releases it when dropped
+    unsafe { ShareId::from_ptr(&*delegate as *const T as *mut Object) }
+}
+
+impl Deref for Panel {
+    type Target = dyn PanelBackend;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.panel
+    }
+}
+
+impl fmt::Debug for Panel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Panel")
+            .field("backend", &Arc::as_ptr(&self.panel.backend))
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util,
+        testing::{app, main_screen, window},
+        Edge, InvalidStyleMask, ManagerExt, PanelLevel, WebviewWindowExt,
+    };
+
+    #[test]
+    fn invalid_style_mask_is_not_applied() {
+        let app = app(test_util::init());
+        let panel = window(&app, "main").to_panel().unwrap();
+
+        assert_eq!(
+            panel.set_style_mask(PanelStyleMask::HUD_WINDOW),
+            Err(Error::InvalidStyleMask(InvalidStyleMask::HudWithoutUtility))
+        );
+
+        let fake = test_util::fake_panel(&app, "main").unwrap();
+        fake.assert_not_called(&test_util::PanelCall::SetStyleMask(
+            PanelStyleMask::HUD_WINDOW,
+        ));
+
+        let hud = PanelStyleMask::HUD_WINDOW | PanelStyleMask::UTILITY_WINDOW;
+        panel.set_style_mask(hud).unwrap();
+        assert_eq!(panel.style_mask(), hud);
+    }
+
+    #[test]
+    fn panels_can_be_driven_from_other_threads() {
+        let app = app(test_util::init());
+        let panel = window(&app, "main").to_panel().unwrap();
+
+        let level = std::thread::spawn(move || {
+            panel.show();
+            panel
+                .run_on_main_thread(|panel| {
+                    panel.set_level(PanelLevel::Floating);
+                    panel.level()
+                })
+                .unwrap()
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(level, PanelLevel::Floating);
+
+        let fake = test_util::fake_panel(&app, "main").unwrap();
+        fake.assert_visible();
+        fake.assert_level(PanelLevel::Floating);
+    }
+
+    #[test]
+    fn every_handle_shares_the_subscribers() {
+        let app = app(test_util::init());
+        let panel = window(&app, "main").to_panel().unwrap();
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let received = events.clone();
+        let menu_bar =
+            panel.on_event(move |event| received.lock().unwrap().push(("menu bar", event)));
+        let received = events.clone();
+        app.get_webview_panel("main")
+            .unwrap()
+            .on_event(move |event| received.lock().unwrap().push(("analytics", event)));
+
+        test_util::emit_event(&panel, PanelEvent::DidResignKey);
+        assert!(panel.off(menu_bar));
+        test_util::emit_event(
+            &app.get_webview_panel("main").unwrap(),
+            PanelEvent::WillClose,
+        );
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                ("menu bar", PanelEvent::DidResignKey),
+                ("analytics", PanelEvent::DidResignKey),
+                ("analytics", PanelEvent::WillClose),
+            ]
+        );
+    }
+
+    #[test]
+    fn panels_are_placed_in_tauri_coordinates() {
+        use tauri::{LogicalPosition, PhysicalSize};
+
+        let app = app(test_util::init());
+        let panel = window(&app, "main").to_panel().unwrap();
+
+        assert_eq!(
+            panel.set_position(LogicalPosition::new(0.0, 0.0)),
+            Err(Error::ScreenUnavailable)
+        );
+
+        test_util::set_screens(&app, vec![main_screen()]);
+        panel.set_frame(Rect::new(0.0, 0.0, 320.0, 80.0), false);
+
+        panel
+            .set_position(LogicalPosition::new(100.0, 50.0))
+            .unwrap();
+        assert_eq!(panel.frame(), Rect::new(100.0, 770.0, 320.0, 80.0));
+
+        panel.set_size(PhysicalSize::new(800, 400)).unwrap();
+        assert_eq!(panel.frame(), Rect::new(100.0, 650.0, 400.0, 200.0));
+    }
+
+    #[test]
+    fn positions_round_trip_through_tauri_coordinates() {
+        use tauri::{PhysicalPosition, PhysicalSize};
+
+        let app = app(test_util::init());
+        let panel = window(&app, "main").to_panel().unwrap();
+
+        assert_eq!(panel.position(), Err(Error::ScreenUnavailable));
+
+        test_util::set_screens(&app, vec![main_screen()]);
+        panel.set_frame(Rect::new(0.0, 0.0, 320.0, 80.0), false);
+
+        panel.set_position(PhysicalPosition::new(200, 100)).unwrap();
+        assert_eq!(panel.position(), Ok(PhysicalPosition::new(200, 100)));
+        assert_eq!(panel.size(), Ok(PhysicalSize::new(640, 160)));
+
+        panel.set_size(PhysicalSize::new(800, 400)).unwrap();
+        assert_eq!(panel.position(), Ok(PhysicalPosition::new(200, 100)));
+        assert_eq!(panel.size(), Ok(PhysicalSize::new(800, 400)));
+    }
+
+    #[test]
+    fn panels_are_placed_next_to_their_anchor() {
+        let app = app(test_util::init());
+        let panel = window(&app, "popover").to_panel().unwrap();
+        test_util::set_screens(&app, vec![main_screen()]);
+        panel.set_frame(Rect::new(0.0, 0.0, 300.0, 200.0), false);
+
+        // A menu bar item at the right end of the menu bar
+        let placement = panel
+            .position_relative_to(
+                Rect::new(1400.0, 875.0, 30.0, 25.0),
+                PlacementOptions::new(Edge::Bottom).offset(4.0),
+            )
+            .unwrap();
+
+        assert_eq!(placement.edge, Edge::Bottom);
+        assert_eq!(placement.frame, Rect::new(1140.0, 671.0, 300.0, 200.0));
+        assert_eq!(panel.frame(), placement.frame);
+    }
+}
@@ -0,0 +1,594 @@
+//! Remembers the frames of panels across launches, see
+//! [`ManagerExt::remember_panel_frame`](crate::ManagerExt::remember_panel_frame).
+//!
+//! Frames are saved by window label to `nspanel-frames.json` in the app data directory:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "frames": {
+//!     "hud": {
+//!       "frame": { "origin": { "x": 100.0, "y": 200.0 }, "size": { "width": 320.0, "height": 80.0 } },
+//!       "screen": { "id": 1, "frame": { ... }, "visibleFrame": { ... }, "scaleFactor": 2.0 }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Changes are written in batches shortly after they happen, when the panel's window is
+//! destroyed and when the app exits. Saving is best effort: failing to read or write the file
+//! is logged and never fails the panel it concerns.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Runtime};
+
+use crate::{Error, ManagerExt, Rect, Screen};
+
+pub(crate) const FILE_NAME: &str = "nspanel-frames.json";
+
+/// The version of the file's layout, files of any other version are ignored.
+const VERSION: u32 = 1;
+
+/// How long changes wait to be written, so a panel being dragged writes the file once.
+const FLUSH_DELAY: Duration = Duration::from_millis(500);
+
+/// Where a panel was when it last moved or resized.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SavedFrame {
+    pub frame: Rect,
+    /// The screen showing most of the panel, if it was on screen.
+    pub screen: Option<Screen>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FrameFile {
+    version: u32,
+    #[serde(default)]
+    frames: BTreeMap<String, SavedFrame>,
+}
+
+#[derive(Deserialize)]
+struct Versioned {
+    version: u32,
+}
+
+/// The saved frames and the labels whose frame is remembered.
+#[derive(Clone)]
+pub(crate) struct FrameStore {
+    frames: Arc<Mutex<Frames>>,
+    /// Held while the file is read or written, so writes land in the order of their changes.
+    /// `frames` is never held for it, frames are saved on the main thread.
+    file: Arc<Mutex<()>>,
+}
+
+struct Frames {
+    path: Option<PathBuf>,
+    /// Read from the file the first time a frame is remembered.
+    saved: Option<BTreeMap<String, SavedFrame>>,
+    remembered: HashSet<String>,
+    /// Whether `saved` has changes the file does not have yet.
+    dirty: bool,
+    /// Whether a flush is waiting for [`FLUSH_DELAY`].
+    flush_scheduled: bool,
+}
+
+impl FrameStore {
+    /// A store saving to `path`, or keeping frames in memory without it.
+    pub(crate) fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            frames: Arc::new(Mutex::new(Frames {
+                path,
+                saved: None,
+                remembered: HashSet::new(),
+                dirty: false,
+                flush_scheduled: false,
+            })),
+            file: Arc::default(),
+        }
+    }
+
+    #[cfg(all(test, feature = "test-util"))]
+    pub(crate) fn set_path(&self, path: PathBuf) {
+        let mut frames = self.lock();
+        frames.path = Some(path);
+        frames.saved = None;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Frames> {
+        self.frames.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_file(&self) -> MutexGuard<'_, ()> {
+        self.file.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Starts remembering the frame of `label`, returns the frame saved by a previous launch.
+    pub(crate) fn remember(&self, label: &str) -> Option<SavedFrame> {
+        self.load();
+
+        let mut frames = self.lock();
+        frames.remembered.insert(label.to_owned());
+        frames.saved.as_ref()?.get(label).copied()
+    }
+
+    /// Reads the file the first time a frame is remembered.
+    fn load(&self) {
+        let _file = self.lock_file();
+
+        let path = {
+            let frames = self.lock();
+            if frames.saved.is_some() {
+                return;
+            }
+            frames.path.clone()
+        };
+
+        let saved = path.as_deref().map(read).unwrap_or_default();
+        self.lock().saved = Some(saved);
+    }
+
+    /// Stops remembering the frame of `label`, what was saved is kept for the next launch.
+    pub(crate) fn forget(&self, label: &str) {
+        self.lock().remembered.remove(label);
+    }
+
+    pub(crate) fn is_remembered(&self, label: &str) -> bool {
+        self.lock().remembered.contains(label)
+    }
+
+    /// Saves the frame of `label`, the file is written [`FLUSH_DELAY`] after the first change
+    /// not written yet.
+    pub(crate) fn save(&self, label: &str, frame: SavedFrame) {
+        let mut frames = self.lock();
+
+        if !frames.remembered.contains(label) {
+            return;
+        }
+
+        // Loaded by `remember` before the label is remembered
+        let saved = frames.saved.get_or_insert_with(BTreeMap::new);
+
+        if saved.insert(label.to_owned(), frame) == Some(frame) {
+            return;
+        }
+
+        frames.dirty = true;
+
+        if frames.path.is_none() || frames.flush_scheduled {
+            return;
+        }
+
+        frames.flush_scheduled = true;
+
+        let store = self.clone();
+        thread::spawn(move || {
+            thread::sleep(FLUSH_DELAY);
+            store.flush();
+        });
+    }
+
+    /// Writes the changes not written yet.
+    pub(crate) fn flush(&self) {
+        let _file = self.lock_file();
+
+        let (path, frames) = {
+            let mut frames = self.lock();
+            frames.flush_scheduled = false;
+
+            if !std::mem::take(&mut frames.dirty) {
+                return;
+            }
+
+            let Some(path) = frames.path.clone() else {
+                return;
+            };
+            (path, frames.saved.clone().unwrap_or_default())
+        };
+
+        if let Err(e) = write(&path, frames) {
+            log::warn!("failed to save panel frames to {}: {e}", path.display());
+        }
+    }
+}
+
+/// Reads the frames saved at `path`, a missing, unreadable or outdated file has none.
+fn read(path: &Path) -> BTreeMap<String, SavedFrame> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return BTreeMap::new(),
+        Err(e) => {
+            log::warn!("failed to read panel frames from {}: {e}", path.display());
+            return BTreeMap::new();
+        }
+    };
+
+    match serde_json::from_str::<Versioned>(&contents) {
+        Ok(Versioned { version: VERSION }) => {}
+        Ok(Versioned { version }) => {
+            log::warn!(
+                "ignoring panel frames from {}, version {version} is not supported",
+                path.display()
+            );
+            return BTreeMap::new();
+        }
+        Err(e) => {
+            log::warn!("ignoring invalid panel frames from {}: {e}", path.display());
+            return BTreeMap::new();
+        }
+    }
+
+    match serde_json::from_str::<FrameFile>(&contents) {
+        Ok(file) => file.frames,
+        Err(e) => {
+            log::warn!("ignoring invalid panel frames from {}: {e}", path.display());
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Replaces the file at `path` in one step, so a crash never leaves it half written.
+fn write(path: &Path, frames: BTreeMap<String, SavedFrame>) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let file = FrameFile {
+        version: VERSION,
+        frames,
+    };
+    let contents = serde_json::to_vec_pretty(&file).map_err(io::Error::other)?;
+
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)
+}
+
+/// Where a panel saved at `saved` goes on the screens connected now.
+///
+/// The panel keeps its place on the screen it was on, even if that screen was moved around.
+/// When that screen is gone it keeps its place relative to the menu bar screen. Either way it is
+/// moved, and shrunk if needed, to fit the screen's visible frame.
+pub(crate) fn restored_frame(saved: &SavedFrame, screens: &[Screen]) -> Rect {
+    let Some(main) = screens.first() else {
+        // Nothing to check the frame against
+        return saved.frame;
+    };
+
+    if let Some(was_on) = &saved.screen {
+        let screen = screens
+            .iter()
+            .find(|screen| screen.id == was_on.id)
+            .unwrap_or(main);
+
+        let mut frame = saved.frame;
+        frame.origin.x += screen.frame.origin.x - was_on.frame.origin.x;
+        frame.origin.y += screen.frame.origin.y - was_on.frame.origin.y;
+
+        return frame.clamped_to(&screen.visible_frame);
+    }
+
+    match Screen::for_frame(&saved.frame, screens) {
+        Some(screen) => saved.frame.clamped_to(&screen.visible_frame),
+        // Saved off screen, center it on the menu bar screen
+        None => {
+            let visible = main.visible_frame;
+            let mut frame = saved.frame;
+            frame.origin.x = visible.origin.x + (visible.size.width - frame.size.width) / 2.0;
+            frame.origin.y = visible.origin.y + (visible.size.height - frame.size.height) / 2.0;
+            frame.clamped_to(&visible)
+        }
+    }
+}
+
+/// Restores the frame saved for `label` and remembers it from now on.
+pub(crate) fn remember<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Result<(), Error> {
+    let panel = manager.get_webview_panel(label)?;
+
+    if let Some(saved) = manager.state::<FrameStore>().remember(label) {
+        panel.run_on_main_thread(move |panel| {
+            panel.set_frame(restored_frame(&saved, &panel.screens()), false);
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Saves the current frame of `label` if it is remembered.
+///
+/// Nothing is saved while the panel is animated, the frames it goes through are not where it
+/// rests.
+pub(crate) fn save<R: Runtime, M: Manager<R>>(manager: &M, label: &str) {
+    let store = manager.state::<FrameStore>();
+
+    if !store.is_remembered(label) {
+        return;
+    }
+
+    let Ok(panel) = manager.get_webview_panel(label) else {
+        return;
+    };
+
+    if panel.is_animating() {
+        return;
+    }
+
+    if let Ok(frame) = panel.run_on_main_thread(|panel| SavedFrame {
+        frame: panel.frame(),
+        screen: panel.screen(),
+    }) {
+        store.save(label, frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Size;
+
+    fn screen(id: u32, x: f64, y: f64) -> Screen {
+        Screen {
+            id,
+            frame: Rect::new(x, y, 1440.0, 900.0),
+            visible_frame: Rect::new(x, y, 1440.0, 875.0),
+            scale_factor: 2.0,
+        }
+    }
+
+    fn saved(frame: Rect, screen: Option<Screen>) -> SavedFrame {
+        SavedFrame { frame, screen }
+    }
+
+    fn path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("tauri-nspanel-{}", std::process::id()))
+            .join(name)
+            .join(FILE_NAME)
+    }
+
+    #[test]
+    fn frames_are_restored_on_their_screen() {
+        let frame = Rect::new(1600.0, 100.0, 320.0, 80.0);
+        let saved = saved(frame, Some(screen(2, 1440.0, 0.0)));
+
+        assert_eq!(
+            restored_frame(&saved, &[screen(1, 0.0, 0.0), screen(2, 1440.0, 0.0)]),
+            frame
+        );
+
+        // The screen now sits left of the main one
+        assert_eq!(
+            restored_frame(&saved, &[screen(1, 0.0, 0.0), screen(2, -1440.0, 0.0)]),
+            Rect::new(-1280.0, 100.0, 320.0, 80.0)
+        );
+    }
+
+    #[test]
+    fn frames_of_disconnected_screens_move_to_the_main_screen() {
+        let saved = saved(
+            Rect::new(1600.0, 100.0, 320.0, 80.0),
+            Some(screen(2, 1440.0, 0.0)),
+        );
+
+        assert_eq!(
+            restored_frame(&saved, &[screen(1, 0.0, 0.0)]),
+            Rect::new(160.0, 100.0, 320.0, 80.0)
+        );
+    }
+
+    #[test]
+    fn frames_are_kept_inside_the_visible_frame() {
+        // Saved on a larger screen
+        let mut small = screen(1, 0.0, 0.0);
+        small.frame.size = Size::new(1280.0, 800.0);
+        small.visible_frame.size = Size::new(1280.0, 775.0);
+        let under_the_menu_bar = saved(
+            Rect::new(1200.0, 760.0, 320.0, 80.0),
+            Some(screen(1, 0.0, 0.0)),
+        );
+
+        assert_eq!(
+            restored_frame(&under_the_menu_bar, &[small]),
+            Rect::new(960.0, 695.0, 320.0, 80.0)
+        );
+
+        // Larger than the screen
+        let too_large = saved(Rect::new(-10.0, -10.0, 2000.0, 1000.0), None);
+        assert_eq!(
+            restored_frame(&too_large, &[screen(1, 0.0, 0.0)]),
+            Rect::new(0.0, 0.0, 1440.0, 875.0)
+        );
+    }
+
+    #[test]
+    fn frames_without_a_screen_stay_where_they_were_if_visible() {
+        let screens = [screen(1, 0.0, 0.0), screen(2, 1440.0, 0.0)];
+
+        assert_eq!(
+            restored_frame(
+                &saved(Rect::new(1500.0, 10.0, 100.0, 100.0), None),
+                &screens
+            ),
+            Rect::new(1500.0, 10.0, 100.0, 100.0)
+        );
+        assert_eq!(
+            restored_frame(
+                &saved(Rect::new(5000.0, 10.0, 100.0, 100.0), None),
+                &screens
+            ),
+            Rect::new(670.0, 387.5, 100.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn frames_are_kept_as_is_without_screens() {
+        let frame = Rect::new(5000.0, 10.0, 100.0, 100.0);
+
+        assert_eq!(
+            restored_frame(&saved(frame, Some(screen(1, 0.0, 0.0))), &[]),
+            frame
+        );
+    }
+
+    #[test]
+    fn frames_are_saved_for_the_next_launch() {
+        let path = path("round-trip");
+        let _ = fs::remove_file(&path);
+        let frame = saved(Rect::new(1.0, 2.0, 3.0, 4.0), Some(screen(1, 0.0, 0.0)));
+
+        let store = FrameStore::new(Some(path.clone()));
+        assert_eq!(store.remember("hud"), None);
+        store.save("hud", frame);
+        store.save("other", frame);
+        store.flush();
+
+        let store = FrameStore::new(Some(path.clone()));
+        assert_eq!(store.remember("hud"), Some(frame));
+        assert_eq!(store.remember("other"), None);
+
+        let file: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(file["version"], 1);
+        assert_eq!(file["frames"]["hud"]["screen"]["id"], 1);
+    }
+
+    #[test]
+    fn frames_are_written_once_flushed() {
+        let path = path("flush");
+        let _ = fs::remove_file(&path);
+        let frame = saved(Rect::new(1.0, 2.0, 3.0, 4.0), None);
+
+        let store = FrameStore::new(Some(path.clone()));
+        store.remember("hud");
+        store.save("hud", frame);
+        assert!(!path.exists());
+
+        store.flush();
+        assert_eq!(read(&path).get("hud"), Some(&frame));
+    }
+
+    #[test]
+    fn saves_do_not_wait_for_the_file() {
+        let store = FrameStore::new(None);
+        store.remember("hud");
+
+        let _file = store.lock_file();
+        store.save("hud", saved(Rect::new(1.0, 2.0, 3.0, 4.0), None));
+
+        assert!(store.lock().dirty);
+    }
+
+    #[test]
+    fn unusable_files_are_ignored() {
+        let path = path("unusable");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(read(&path).is_empty());
+
+        fs::write(&path, r#"{ "version": 2, "frames": { "hud": [] } }"#).unwrap();
+        assert!(read(&path).is_empty());
+
+        fs::remove_file(&path).unwrap();
+        assert!(read(&path).is_empty());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn remembered_frames_survive_a_relaunch() {
+        use tauri::{test::MockRuntime, App, WebviewUrl};
+
+        use crate::{
+            test_util,
+            testing::{app, main_screen},
+            PanelBuilder,
+        };
+
+        let path = std::env::temp_dir()
+            .join(format!("tauri-nspanel-{}", std::process::id()))
+            .join("relaunch")
+            .join(FILE_NAME);
+        let _ = std::fs::remove_file(&path);
+
+        let launch = || {
+            let app = app(test_util::init());
+            app.state::<FrameStore>().set_path(path.clone());
+            test_util::set_screens(&app, vec![main_screen()]);
+            app
+        };
+        let build = |app: &App<MockRuntime>, label| {
+            PanelBuilder::new(app, label, WebviewUrl::default())
+                .remember_frame()
+                .build()
+                .unwrap()
+        };
+
+        let app = launch();
+        let panel = build(&app, "hud");
+        panel.set_frame(Rect::new(100.0, 200.0, 320.0, 80.0), false);
+        save(&app, "hud");
+        app.state::<FrameStore>().flush();
+
+        let app = launch();
+        let panel = build(&app, "hud");
+        assert_eq!(panel.frame(), Rect::new(100.0, 200.0, 320.0, 80.0));
+
+        // Nothing was saved for this one
+        build(&app, "other");
+        let fake = test_util::fake_panel(&app, "other").unwrap();
+        assert!(!fake
+            .calls()
+            .iter()
+            .any(|call| matches!(call, test_util::PanelCall::SetFrame(..))));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn animated_frames_are_not_saved() {
+        use tauri::WebviewUrl;
+
+        use crate::{
+            test_util,
+            testing::{app, main_screen},
+            Animation, Edge, PanelBuilder, Transition,
+        };
+
+        let app = app(test_util::init());
+        let store = app.state::<FrameStore>().inner().clone();
+        store.set_path(path("animated"));
+        test_util::set_screens(&app, vec![main_screen()]);
+
+        let panel = PanelBuilder::new(&app, "hud", WebviewUrl::default())
+            .remember_frame()
+            .build()
+            .unwrap();
+        let frame = Rect::new(100.0, 200.0, 320.0, 80.0);
+        panel.set_frame(frame, false);
+        save(&app, "hud");
+
+        // The panel starts above the screen and slides down
+        panel
+            .show_animated(Animation::new(Transition::Slide(Edge::Top)), |_| ())
+            .unwrap();
+        save(&app, "hud");
+        assert_eq!(store.remember("hud").unwrap().frame, frame);
+
+        test_util::fake_panel(&app, "hud")
+            .unwrap()
+            .finish_animations();
+        panel.set_frame(Rect::new(0.0, 0.0, 320.0, 80.0), false);
+        save(&app, "hud");
+        assert_eq!(
+            store.remember("hud").unwrap().frame,
+            Rect::new(0.0, 0.0, 320.0, 80.0)
+        );
+    }
+}
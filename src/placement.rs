@@ -0,0 +1,576 @@
+//! Places a panel next to an anchor rectangle, the way popovers are: below a menu bar item,
+//! flipped above it when there is no room below, and kept inside the screen's visible frame.
+//!
+//! Everything is in AppKit screen coordinates, see [`Point`](crate::Point).
+
+use serde::Deserialize;
+
+use crate::{Rect, Size};
+
+/// The side of the anchor the panel is placed on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Edge {
+    Top,
+    #[default]
+    Bottom,
+    Left,
+    Right,
+}
+
+/// How the panel lines up with the anchor along the edge it is placed on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// Aligns the left sides above or below the anchor, the top sides beside it.
+    Start,
+    #[default]
+    Center,
+    /// Aligns the right sides above or below the anchor, the bottom sides beside it.
+    End,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlacementOptions {
+    /// The edge to place the panel on when it fits there.
+    pub edge: Edge,
+    pub alignment: Alignment,
+    /// The gap between the anchor and the panel, in points.
+    pub offset: f64,
+}
+
+/// Where [`place`] put the panel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    pub frame: Rect,
+    /// The edge the panel ended up on, the opposite of the preferred one when it was flipped.
+    pub edge: Edge,
+}
+
+impl Edge {
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Self::Top | Self::Bottom)
+    }
+}
+
+impl PlacementOptions {
+    pub fn new(edge: Edge) -> Self {
+        Self {
+            edge,
+            ..Default::default()
+        }
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// Places a panel of `size` next to `anchor`.
+///
+/// `screens` are the visible frames of the connected screens, the panel stays on the one
+/// showing most of the anchor, or the closest one if the anchor is off screen. The panel is
+/// flipped to the opposite edge when it does not fit on the preferred one but fits there.
+/// When it fits on neither, it goes on the roomier edge and is pushed inside the screen. A
+/// panel larger than the screen is lined up with the screen's top left corner.
+///
+/// Without screens the panel is placed as preferred.
+pub fn place(size: Size, anchor: Rect, options: &PlacementOptions, screens: &[Rect]) -> Placement {
+    let Some(bounds) = screen_for(&anchor, screens) else {
+        return Placement {
+            frame: beside(size, anchor, options.edge, options),
+            edge: options.edge,
+        };
+    };
+
+    let preferred = options.edge;
+    let needed = extent(size, preferred);
+    let preferred_room = room(anchor, preferred, options.offset, &bounds);
+    let opposite_room = room(anchor, preferred.opposite(), options.offset, &bounds);
+
+    // Flip when only the opposite edge has room, or when neither has and it is the roomier
+    let flip =
+        preferred_room < needed && (opposite_room >= needed || opposite_room > preferred_room);
+    let edge = if flip {
+        preferred.opposite()
+    } else {
+        preferred
+    };
+
+    let mut frame = beside(size, anchor, edge, options);
+    frame.origin.x = clamp(
+        frame.origin.x,
+        size.width,
+        bounds.origin.x,
+        bounds.max_x(),
+        false,
+    );
+    frame.origin.y = clamp(
+        frame.origin.y,
+        size.height,
+        bounds.origin.y,
+        bounds.max_y(),
+        true,
+    );
+
+    Placement { frame, edge }
+}
+
+/// The visible frame showing most of `anchor`, else the one closest to its center.
+fn screen_for(anchor: &Rect, screens: &[Rect]) -> Option<Rect> {
+    let center = (
+        anchor.origin.x + anchor.size.width / 2.0,
+        anchor.origin.y + anchor.size.height / 2.0,
+    );
+    let distance = |screen: &Rect| {
+        let dx = (screen.origin.x - center.0)
+            .max(center.0 - screen.max_x())
+            .max(0.0);
+        let dy = (screen.origin.y - center.1)
+            .max(center.1 - screen.max_y())
+            .max(0.0);
+        dx * dx + dy * dy
+    };
+
+    screens
+        .iter()
+        .copied()
+        .fold(None, |best: Option<Rect>, screen| match best {
+            Some(best)
+                if best.overlap(anchor) > screen.overlap(anchor)
+                    || (best.overlap(anchor) == screen.overlap(anchor)
+                        && distance(&best) <= distance(&screen)) =>
+            {
+                Some(best)
+            }
+            _ => Some(screen),
+        })
+}
+
+/// The frame of `size` on `edge` of `anchor`, before it is kept on screen.
+fn beside(size: Size, anchor: Rect, edge: Edge, options: &PlacementOptions) -> Rect {
+    let offset = options.offset;
+
+    let (x, y) = match edge {
+        Edge::Top => (0.0, anchor.max_y() + offset),
+        Edge::Bottom => (0.0, anchor.origin.y - offset - size.height),
+        Edge::Left => (anchor.origin.x - offset - size.width, 0.0),
+        Edge::Right => (anchor.max_x() + offset, 0.0),
+    };
+
+    if edge.is_vertical() {
+        let x = match options.alignment {
+            Alignment::Start => anchor.origin.x,
+            Alignment::Center => anchor.origin.x + (anchor.size.width - size.width) / 2.0,
+            Alignment::End => anchor.max_x() - size.width,
+        };
+        Rect::new(x, y, size.width, size.height)
+    } else {
+        let y = match options.alignment {
+            Alignment::Start => anchor.max_y() - size.height,
+            Alignment::Center => anchor.origin.y + (anchor.size.height - size.height) / 2.0,
+            Alignment::End => anchor.origin.y,
+        };
+        Rect::new(x, y, size.width, size.height)
+    }
+}
+
+/// The room between `anchor` and the side of `bounds` past its `edge`.
+fn room(anchor: Rect, edge: Edge, offset: f64, bounds: &Rect) -> f64 {
+    match edge {
+        Edge::Top => bounds.max_y() - anchor.max_y() - offset,
+        Edge::Bottom => anchor.origin.y - offset - bounds.origin.y,
+        Edge::Left => anchor.origin.x - offset - bounds.origin.x,
+        Edge::Right => bounds.max_x() - anchor.max_x() - offset,
+    }
+}
+
+/// The room a panel of `size` needs on `edge`.
+fn extent(size: Size, edge: Edge) -> f64 {
+    if edge.is_vertical() {
+        size.height
+    } else {
+        size.width
+    }
+}
+
+/// Keeps `[start, start + length]` inside `[min, max]`, against `max` when it cannot fit.
+fn clamp(start: f64, length: f64, min: f64, max: f64, prefer_max: bool) -> f64 {
+    if length > max - min {
+        if prefer_max {
+            max - length
+        } else {
+            min
+        }
+    } else {
+        start.min(max - length).max(min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1440x875 visible frame under a 25 points menu bar.
+    const MAIN: Rect = Rect {
+        origin: crate::Point { x: 0.0, y: 0.0 },
+        size: Size {
+            width: 1440.0,
+            height: 875.0,
+        },
+    };
+
+    /// A 1920x1080 screen right of the main one, without menu bar.
+    const RIGHT: Rect = Rect {
+        origin: crate::Point { x: 1440.0, y: 0.0 },
+        size: Size {
+            width: 1920.0,
+            height: 1080.0,
+        },
+    };
+
+    const PANEL: Size = Size {
+        width: 300.0,
+        height: 200.0,
+    };
+
+    fn options(edge: Edge, alignment: Alignment) -> PlacementOptions {
+        PlacementOptions::new(edge).alignment(alignment)
+    }
+
+    fn placed(anchor: Rect, options: PlacementOptions, screens: &[Rect]) -> (Rect, Edge) {
+        let placement = place(PANEL, anchor, &options, screens);
+        (placement.frame, placement.edge)
+    }
+
+    #[test]
+    fn panels_go_on_the_preferred_edge_when_they_fit() {
+        let anchor = Rect::new(600.0, 400.0, 100.0, 20.0);
+
+        let cases = [
+            (Edge::Bottom, Rect::new(500.0, 200.0, 300.0, 200.0)),
+            (Edge::Top, Rect::new(500.0, 420.0, 300.0, 200.0)),
+            (Edge::Left, Rect::new(300.0, 310.0, 300.0, 200.0)),
+            (Edge::Right, Rect::new(700.0, 310.0, 300.0, 200.0)),
+        ];
+
+        for (edge, expected) in cases {
+            assert_eq!(
+                placed(anchor, options(edge, Alignment::Center), &[MAIN]),
+                (expected, edge),
+                "{edge:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn panels_are_aligned_with_the_anchor() {
+        let anchor = Rect::new(600.0, 400.0, 100.0, 20.0);
+
+        let cases = [
+            (Edge::Bottom, Alignment::Start, (600.0, 200.0)),
+            (Edge::Bottom, Alignment::End, (400.0, 200.0)),
+            (Edge::Top, Alignment::Start, (600.0, 420.0)),
+            (Edge::Top, Alignment::End, (400.0, 420.0)),
+            (Edge::Right, Alignment::Start, (700.0, 220.0)),
+            (Edge::Right, Alignment::End, (700.0, 400.0)),
+            (Edge::Left, Alignment::Start, (300.0, 220.0)),
+            (Edge::Left, Alignment::End, (300.0, 400.0)),
+        ];
+
+        for (edge, alignment, (x, y)) in cases {
+            assert_eq!(
+                placed(anchor, options(edge, alignment), &[MAIN]),
+                (Rect::new(x, y, 300.0, 200.0), edge),
+                "{edge:?} {alignment:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn the_offset_separates_the_panel_from_the_anchor() {
+        let anchor = Rect::new(600.0, 400.0, 100.0, 20.0);
+
+        let cases = [
+            (Edge::Bottom, (500.0, 192.0)),
+            (Edge::Top, (500.0, 428.0)),
+            (Edge::Left, (292.0, 310.0)),
+            (Edge::Right, (708.0, 310.0)),
+        ];
+
+        for (edge, (x, y)) in cases {
+            let options = options(edge, Alignment::Center).offset(8.0);
+            assert_eq!(
+                placed(anchor, options, &[MAIN]),
+                (Rect::new(x, y, 300.0, 200.0), edge),
+                "{edge:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn panels_flip_when_the_preferred_edge_has_no_room() {
+        let cases = [
+            // A menu bar item, no room below the bottom of the visible frame
+            (
+                Edge::Top,
+                Rect::new(600.0, 700.0, 100.0, 20.0),
+                Rect::new(500.0, 500.0, 300.0, 200.0),
+                Edge::Bottom,
+            ),
+            (
+                Edge::Bottom,
+                Rect::new(600.0, 100.0, 100.0, 20.0),
+                Rect::new(500.0, 120.0, 300.0, 200.0),
+                Edge::Top,
+            ),
+            (
+                Edge::Left,
+                Rect::new(100.0, 400.0, 20.0, 20.0),
+                Rect::new(120.0, 310.0, 300.0, 200.0),
+                Edge::Right,
+            ),
+            (
+                Edge::Right,
+                Rect::new(1300.0, 400.0, 20.0, 20.0),
+                Rect::new(1000.0, 310.0, 300.0, 200.0),
+                Edge::Left,
+            ),
+        ];
+
+        for (preferred, anchor, frame, edge) in cases {
+            assert_eq!(
+                placed(anchor, options(preferred, Alignment::Center), &[MAIN]),
+                (frame, edge),
+                "{preferred:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn offsets_count_when_deciding_to_flip() {
+        // 200 points of room below, exactly the panel's height
+        let anchor = Rect::new(600.0, 200.0, 100.0, 20.0);
+
+        assert_eq!(
+            placed(anchor, options(Edge::Bottom, Alignment::Center), &[MAIN]).1,
+            Edge::Bottom
+        );
+        assert_eq!(
+            placed(
+                anchor,
+                options(Edge::Bottom, Alignment::Center).offset(1.0),
+                &[MAIN]
+            )
+            .1,
+            Edge::Top
+        );
+    }
+
+    #[test]
+    fn panels_fitting_nowhere_take_the_roomier_edge_and_stay_on_screen() {
+        let short = Rect::new(0.0, 0.0, 1440.0, 300.0);
+
+        // 120 points below, 160 above
+        let (frame, edge) = placed(
+            Rect::new(600.0, 120.0, 100.0, 20.0),
+            options(Edge::Bottom, Alignment::Center),
+            &[short],
+        );
+        assert_eq!(edge, Edge::Top);
+        assert_eq!(frame, Rect::new(500.0, 100.0, 300.0, 200.0));
+
+        // 160 points below, 120 above
+        let (frame, edge) = placed(
+            Rect::new(600.0, 160.0, 100.0, 20.0),
+            options(Edge::Bottom, Alignment::Center),
+            &[short],
+        );
+        assert_eq!(edge, Edge::Bottom);
+        assert_eq!(frame, Rect::new(500.0, 0.0, 300.0, 200.0));
+    }
+
+    #[test]
+    fn panels_are_kept_inside_the_visible_frame_along_the_edge() {
+        let cases = [
+            // A menu bar item at the right end of the screen
+            (
+                Rect::new(1400.0, 855.0, 30.0, 20.0),
+                Edge::Bottom,
+                Rect::new(1140.0, 655.0, 300.0, 200.0),
+            ),
+            // At the left end
+            (
+                Rect::new(10.0, 855.0, 30.0, 20.0),
+                Edge::Bottom,
+                Rect::new(0.0, 655.0, 300.0, 200.0),
+            ),
+            // Beside an anchor at the top and bottom of the screen
+            (
+                Rect::new(600.0, 860.0, 20.0, 10.0),
+                Edge::Right,
+                Rect::new(620.0, 675.0, 300.0, 200.0),
+            ),
+            (
+                Rect::new(600.0, 5.0, 20.0, 10.0),
+                Edge::Left,
+                Rect::new(300.0, 0.0, 300.0, 200.0),
+            ),
+        ];
+
+        for (anchor, edge, frame) in cases {
+            assert_eq!(
+                placed(anchor, options(edge, Alignment::Center), &[MAIN]),
+                (frame, edge),
+                "{anchor:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn panels_stay_on_the_screen_of_their_anchor() {
+        let screens = [MAIN, RIGHT];
+
+        // Close to the left side of the right screen, clamped to it rather than spilling over
+        let (frame, edge) = placed(
+            Rect::new(1450.0, 500.0, 20.0, 20.0),
+            options(Edge::Bottom, Alignment::Center),
+            &screens,
+        );
+        assert_eq!(
+            (frame, edge),
+            (Rect::new(1440.0, 300.0, 300.0, 200.0), Edge::Bottom)
+        );
+
+        // At the top of the right screen, which is taller than the main one
+        let (frame, edge) = placed(
+            Rect::new(2000.0, 1000.0, 20.0, 20.0),
+            options(Edge::Top, Alignment::Center),
+            &screens,
+        );
+        assert_eq!(
+            (frame, edge),
+            (Rect::new(1860.0, 800.0, 300.0, 200.0), Edge::Bottom)
+        );
+
+        // On the left edge of the right screen, flipped rather than put on the main screen
+        let (frame, edge) = placed(
+            Rect::new(1450.0, 500.0, 20.0, 20.0),
+            options(Edge::Left, Alignment::Center),
+            &screens,
+        );
+        assert_eq!(
+            (frame, edge),
+            (Rect::new(1470.0, 410.0, 300.0, 200.0), Edge::Right)
+        );
+    }
+
+    #[test]
+    fn anchors_across_screens_use_the_one_showing_most_of_them() {
+        let screens = [MAIN, RIGHT];
+
+        let (frame, _) = placed(
+            Rect::new(1430.0, 500.0, 40.0, 20.0),
+            options(Edge::Bottom, Alignment::Start),
+            &screens,
+        );
+        assert_eq!(frame, Rect::new(1440.0, 300.0, 300.0, 200.0));
+
+        let (frame, _) = placed(
+            Rect::new(1400.0, 500.0, 50.0, 20.0),
+            options(Edge::Bottom, Alignment::Start),
+            &screens,
+        );
+        assert_eq!(frame, Rect::new(1140.0, 300.0, 300.0, 200.0));
+    }
+
+    #[test]
+    fn anchors_off_screen_use_the_closest_screen() {
+        let screens = [MAIN, RIGHT];
+
+        // Past the right screen
+        let (frame, _) = placed(
+            Rect::new(4000.0, 500.0, 20.0, 20.0),
+            options(Edge::Bottom, Alignment::Center),
+            &screens,
+        );
+        assert_eq!(frame, Rect::new(3060.0, 300.0, 300.0, 200.0));
+
+        // Below the main screen, the panel cannot go below the anchor
+        let (frame, edge) = placed(
+            Rect::new(600.0, -100.0, 100.0, 20.0),
+            options(Edge::Bottom, Alignment::Center),
+            &screens,
+        );
+        assert_eq!(
+            (frame, edge),
+            (Rect::new(500.0, 0.0, 300.0, 200.0), Edge::Top)
+        );
+    }
+
+    #[test]
+    fn point_anchors_are_supported() {
+        let (frame, edge) = placed(
+            Rect::new(600.0, 400.0, 0.0, 0.0),
+            options(Edge::Right, Alignment::Start),
+            &[MAIN],
+        );
+
+        assert_eq!(
+            (frame, edge),
+            (Rect::new(600.0, 200.0, 300.0, 200.0), Edge::Right)
+        );
+    }
+
+    #[test]
+    fn panels_larger_than_the_screen_line_up_with_its_top_left_corner() {
+        let placement = place(
+            Size::new(2000.0, 1000.0),
+            Rect::new(600.0, 400.0, 100.0, 20.0),
+            &PlacementOptions::default(),
+            &[MAIN],
+        );
+
+        assert_eq!(placement.frame, Rect::new(0.0, -125.0, 2000.0, 1000.0));
+    }
+
+    #[test]
+    fn without_screens_panels_are_placed_as_preferred() {
+        let placement = place(
+            PANEL,
+            Rect::new(600.0, -100.0, 100.0, 20.0),
+            &PlacementOptions::default().offset(4.0),
+            &[],
+        );
+
+        assert_eq!(
+            placement,
+            Placement {
+                frame: Rect::new(500.0, -304.0, 300.0, 200.0),
+                edge: Edge::Bottom,
+            }
+        );
+    }
+
+    #[test]
+    fn edges_have_opposites() {
+        for edge in [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right] {
+            assert_ne!(edge.opposite(), edge);
+            assert_eq!(edge.opposite().opposite(), edge);
+        }
+    }
+}
@@ -0,0 +1,634 @@
+use std::{cell::Cell, ffi::c_void, marker::PhantomData, time::Duration};
+
+use bitflags::bitflags;
+use block::ConcreteBlock;
+use cocoa::{
+    appkit::{NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindowCollectionBehavior},
+    base::{id, nil, BOOL, NO, YES},
+    foundation::{NSAutoreleasePool, NSInteger, NSPoint, NSRect, NSString, NSUInteger},
+};
+use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{self, Class, Object, Sel},
+    sel, sel_impl, Message,
+};
+use objc_foundation::INSObject;
+use objc_id::{Id, ShareId};
+use tauri::{Runtime, WebviewWindow};
+
+use crate::{
+    backend::PanelBackend,
+    kind::{Overlay, PanelKind, Standard},
+    CollectionBehavior, Easing, Error, PanelLevel, PanelStyleMask, Point, Rect, Screen,
+};
+
+bitflags! {
+    struct NSTrackingAreaOptions: u32 {
+        const NSTrackingActiveAlways = 0x80;
+        const NSTrackingMouseEnteredAndExited = 0x01;
+        const NSTrackingMouseMoved = 0x02;
+        const NSTrackingCursorUpdate = 0x04;
+    }
+}
+
+extern "C" {
+    pub fn object_setClass(obj: id, cls: id) -> id;
+    fn objc_setAssociatedObject(object: id, key: *const c_void, value: id, policy: usize);
+    fn objc_getAssociatedObject(object: id, key: *const c_void) -> id;
+}
+
+const OBJC_ASSOCIATION_RETAIN_NONATOMIC: usize = 1;
+
+// Keys for the per panel overrides of the kind's key/main window ability
+static CAN_BECOME_KEY_WINDOW_KEY: u8 = 1;
+static CAN_BECOME_MAIN_WINDOW_KEY: u8 = 2;
+
+// Key of the delegate the panel keeps alive, NSWindow does not retain its delegate
+static DELEGATE_KEY: u8 = 3;
+
+/// A regular panel, see [`Standard`].
+pub type RawNSPanel = RawPanel<Standard>;
+
+/// A non-activating overlay panel, see [`Overlay`].
+pub type RawOverlayPanel = RawPanel<Overlay>;
+
+/// An `NSPanel` subclass whose overrides are decided by the panel kind `K`.
+pub struct RawPanel<K: PanelKind>(PhantomData<K>);
+
+unsafe impl<K: PanelKind> Sync for RawPanel<K> {}
+unsafe impl<K: PanelKind> Send for RawPanel<K> {}
+
+impl<K: PanelKind> INSObject for RawPanel<K> {
+    fn class() -> &'static runtime::Class {
+        Class::get(K::CLASS_NAME).unwrap_or_else(Self::define_class)
+    }
+}
+
+/// Returns the window's `NSWindow`, which may only be touched from the main thread.
+fn ns_window<R: Runtime>(window: &WebviewWindow<R>) -> Result<id, Error> {
+    let is_main_thread: BOOL = unsafe { msg_send![class!(NSThread), isMainThread] };
+    if is_main_thread == NO {
+        return Err(Error::NotOnMainThread);
+    }
+
+    match window.ns_window() {
+        Ok(nswindow) if !nswindow.is_null() => Ok(nswindow as _),
+        Err(tauri::Error::WindowNotFound) => Err(Error::WindowNotFound),
+        _ => Err(Error::NativeHandleUnavailable),
+    }
+}
+
+fn to_bool(flag: bool) -> BOOL {
+    if flag {
+        YES
+    } else {
+        NO
+    }
+}
+
+fn flag_override(this: &Object, key: &'static u8) -> Option<bool> {
+    let value: id = unsafe {
+        objc_getAssociatedObject(
+            this as *const Object as id,
+            key as *const u8 as *const c_void,
+        )
+    };
+
+    if value == nil {
+        None
+    } else {
+        let flag: BOOL = unsafe { msg_send![value, boolValue] };
+        Some(flag == YES)
+    }
+}
+
+fn set_flag_override(this: &Object, key: &'static u8, flag: bool) {
+    unsafe {
+        let value: id = msg_send![class!(NSNumber), numberWithBool: to_bool(flag)];
+        objc_setAssociatedObject(
+            this as *const Object as id,
+            key as *const u8 as *const c_void,
+            value,
+            OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+        );
+    }
+}
+
+/// Retains `delegate` until `window` is deallocated or given another delegate, which
+/// releases the previous one.
+pub(crate) fn retain_delegate(window: id, delegate: id) {
+    unsafe {
+        objc_setAssociatedObject(
+            window,
+            &DELEGATE_KEY as *const u8 as *const c_void,
+            delegate,
+            OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+        );
+    }
+}
+
+impl<K: PanelKind> RawPanel<K> {
+    extern "C" fn can_become_key_window(this: &Object, _: Sel) -> BOOL {
+        to_bool(flag_override(this, &CAN_BECOME_KEY_WINDOW_KEY).unwrap_or(K::CAN_BECOME_KEY_WINDOW))
+    }
+
+    extern "C" fn can_become_main_window(this: &Object, _: Sel) -> BOOL {
+        match flag_override(this, &CAN_BECOME_MAIN_WINDOW_KEY).or(K::CAN_BECOME_MAIN_WINDOW) {
+            Some(flag) => to_bool(flag),
+            None => unsafe { msg_send![super(this, class!(NSPanel)), canBecomeMainWindow] },
+        }
+    }
+
+    extern "C" fn accepts_mouse_moved_events(_: &Object, _: Sel) -> BOOL {
+        to_bool(K::ACCEPTS_MOUSE_MOVED_EVENTS.unwrap_or_default())
+    }
+
+    extern "C" fn ignores_mouse_events(_: &Object, _: Sel) -> BOOL {
+        to_bool(K::IGNORES_MOUSE_EVENTS.unwrap_or_default())
+    }
+
+    extern "C" fn dealloc(this: &mut Object, _cmd: Sel) {
+        unsafe {
+            let superclass = class!(NSObject);
+            let dealloc: extern "C" fn(&mut Object, Sel) =
+                msg_send![super(this, superclass), dealloc];
+            dealloc(this, _cmd);
+        }
+    }
+
+    fn define_class() -> &'static Class {
+        let mut cls = ClassDecl::new(K::CLASS_NAME, class!(NSPanel))
+            .unwrap_or_else(|| panic!("Unable to register {} class", K::CLASS_NAME));
+
+        unsafe {
+            cls.add_method(
+                sel!(canBecomeKeyWindow),
+                Self::can_become_key_window as extern "C" fn(&Object, Sel) -> BOOL,
+            );
+
+            cls.add_method(
+                sel!(canBecomeMainWindow),
+                Self::can_become_main_window as extern "C" fn(&Object, Sel) -> BOOL,
+            );
+
+            if K::ACCEPTS_MOUSE_MOVED_EVENTS.is_some() {
+                cls.add_method(
+                    sel!(acceptsMouseMovedEvents),
+                    Self::accepts_mouse_moved_events as extern "C" fn(&Object, Sel) -> BOOL,
+                );
+            }
+
+            if K::IGNORES_MOUSE_EVENTS.is_some() {
+                cls.add_method(
+                    sel!(ignoresMouseEvents),
+                    Self::ignores_mouse_events as extern "C" fn(&Object, Sel) -> BOOL,
+                );
+            }
+
+            cls.add_method(
+                sel!(dealloc),
+                Self::dealloc as extern "C" fn(&mut Object, Sel),
+            );
+        }
+
+        cls.register()
+    }
+
+    pub fn show(&self) {
+        self.make_first_responder(Some(self.content_view()));
+        self.order_front_regardless();
+
+        if self.can_become_key_window() {
+            self.make_key_window();
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        let flag: BOOL = unsafe { msg_send![self, isVisible] };
+        flag == YES
+    }
+
+    pub fn can_become_key_window(&self) -> bool {
+        let flag: BOOL = unsafe { msg_send![self, canBecomeKeyWindow] };
+        flag == YES
+    }
+
+    /// Override whether this panel can become the key window, regardless of its kind
+    pub fn set_can_become_key_window(&self, flag: bool) {
+        set_flag_override(self.as_object(), &CAN_BECOME_KEY_WINDOW_KEY, flag);
+    }
+
+    /// Override whether this panel can become the main window, regardless of its kind
+    pub fn set_can_become_main_window(&self, flag: bool) {
+        set_flag_override(self.as_object(), &CAN_BECOME_MAIN_WINDOW_KEY, flag);
+    }
+
+    pub fn make_key_window(&self) {
+        let _: () = unsafe { msg_send![self, makeKeyWindow] };
+    }
+
+    pub fn resign_key_window(&self) {
+        let _: () = unsafe { msg_send![self, resignKeyWindow] };
+    }
+
+    pub fn make_key_and_order_front(&self, sender: Option<id>) {
+        let _: () = unsafe { msg_send![self, makeKeyAndOrderFront: sender.unwrap_or(nil)] };
+    }
+
+    pub fn order_front_regardless(&self) {
+        let _: () = unsafe { msg_send![self, orderFrontRegardless] };
+    }
+
+    pub fn order_front(&self, sender: Option<id>) {
+        let _: () = unsafe { msg_send![self, orderFront: sender.unwrap_or(nil)] };
+    }
+
+    pub fn order_out(&self, sender: Option<id>) {
+        let _: () = unsafe { msg_send![self, orderOut: sender.unwrap_or(nil)] };
+    }
+
+    pub fn content_view(&self) -> id {
+        unsafe { msg_send![self, contentView] }
+    }
+
+    pub fn make_first_responder(&self, sender: Option<id>) {
+        if let Some(responder) = sender {
+            let _: () = unsafe { msg_send![self, makeFirstResponder: responder] };
+        } else {
+            let _: () = unsafe { msg_send![self, makeFirstResponder: self] };
+        }
+    }
+
+    pub fn set_level(&self, level: i32) {
+        let _: () = unsafe { msg_send![self, setLevel: level as NSInteger] };
+    }
+
+    pub fn level(&self) -> i32 {
+        let level: NSInteger = unsafe { msg_send![self, level] };
+        level as i32
+    }
+
+    pub fn set_alpha_value(&self, value: f64) {
+        let _: () = unsafe { msg_send![self, setAlphaValue: value] };
+    }
+
+    pub fn alpha_value(&self) -> f64 {
+        unsafe { msg_send![self, alphaValue] }
+    }
+
+    pub fn set_content_size(&self, width: f64, height: f64) {
+        let _: () = unsafe { msg_send![self, setContentSize: (width, height)] };
+    }
+
+    pub fn set_frame(&self, frame: NSRect, display: bool) {
+        let _: () = unsafe { msg_send![self, setFrame: frame display: to_bool(display)] };
+    }
+
+    pub fn set_frame_animated(&self, frame: NSRect, display: bool, animate: bool) {
+        let _: () = unsafe {
+            msg_send![self, setFrame: frame display: to_bool(display) animate: to_bool(animate)]
+        };
+    }
+
+    pub fn frame(&self) -> NSRect {
+        unsafe { msg_send![self, frame] }
+    }
+
+    /// Animates the frame and alpha through the panel's animator proxy in an
+    /// `NSAnimationContext` group, `completion` is called once the group is over.
+    ///
+    /// `timing_function` is the name of a `CAMediaTimingFunction`, such as `easeInEaseOut`.
+    pub fn animate<F>(
+        &self,
+        frame: NSRect,
+        alpha: f64,
+        duration: f64,
+        timing_function: &str,
+        completion: F,
+    ) where
+        F: FnOnce() + 'static,
+    {
+        let panel = self as *const Self as id;
+
+        unsafe {
+            let name = NSString::alloc(nil).init_str(timing_function).autorelease();
+            let timing_function: id =
+                msg_send![class!(CAMediaTimingFunction), functionWithName: name];
+
+            // The changes run before `runAnimationGroup:completionHandler:` returns
+            let changes = ConcreteBlock::new(move |context: id| {
+                let _: () = msg_send![context, setDuration: duration];
+                let _: () = msg_send![context, setTimingFunction: timing_function];
+
+                let animator: id = msg_send![panel, animator];
+                let _: () = msg_send![animator, setFrame: frame display: YES];
+                let _: () = msg_send![animator, setAlphaValue: alpha];
+            })
+            .copy();
+
+            // Blocks are `Fn`, the completion handler is only called once
+            let completion = Cell::new(Some(completion));
+            let completion = ConcreteBlock::new(move || {
+                if let Some(completion) = completion.take() {
+                    completion();
+                }
+            })
+            .copy();
+
+            let _: () = msg_send![
+                class!(NSAnimationContext),
+                runAnimationGroup: &*changes
+                completionHandler: &*completion
+            ];
+        }
+    }
+
+    /// The panel's `NSScreen`, `nil` while it is off screen.
+    pub fn screen(&self) -> id {
+        unsafe { msg_send![self, screen] }
+    }
+
+    pub fn set_style_mask(&self, style_mask: NSUInteger) {
+        let _: () = unsafe { msg_send![self, setStyleMask: style_mask] };
+    }
+
+    pub fn style_mask(&self) -> NSUInteger {
+        unsafe { msg_send![self, styleMask] }
+    }
+
+    pub fn set_collection_behaviour(&self, behaviour: NSWindowCollectionBehavior) {
+        let _: () = unsafe { msg_send![self, setCollectionBehavior: behaviour] };
+    }
+
+    pub fn set_floating_panel(&self, flag: bool) {
+        let _: () = unsafe { msg_send![self, setFloatingPanel: to_bool(flag)] };
+    }
+
+    pub fn set_accepts_mouse_moved_events(&self, flag: bool) {
+        let _: () = unsafe { msg_send![self, setAcceptsMouseMovedEvents: to_bool(flag)] };
+    }
+
+    pub fn set_ignores_mouse_events(&self, flag: bool) {
+        let _: () = unsafe { msg_send![self, setIgnoresMouseEvents: to_bool(flag)] };
+    }
+
+    pub fn set_hides_on_deactivate(&self, flag: bool) {
+        let _: () = unsafe { msg_send![self, setHidesOnDeactivate: to_bool(flag)] };
+    }
+
+    pub fn set_movable_by_window_background(&self, flag: bool) {
+        let _: () = unsafe { msg_send![self, setMovableByWindowBackground: to_bool(flag)] };
+    }
+
+    pub fn released_when_closed(&self, flag: bool) {
+        let _: () = unsafe { msg_send![self, setReleasedWhenClosed: to_bool(flag)] };
+    }
+
+    pub fn close(&self) {
+        let _: () = unsafe { msg_send![self, close] };
+    }
+
+    fn as_id(&self) -> id {
+        self as *const Self as id
+    }
+
+    fn as_object(&self) -> &Object {
+        unsafe { &*(self as *const Self as *const Object) }
+    }
+
+    pub fn handle(&mut self) -> ShareId<Self> {
+        unsafe { ShareId::from_ptr(self as *mut Self) }
+    }
+
+    fn add_tracking_area(&self) {
+        let view: id = self.content_view();
+        let bounds: NSRect = unsafe { NSView::bounds(view) };
+        let track_view: id = unsafe { msg_send![class!(NSTrackingArea), alloc] };
+        let track_view: id = unsafe {
+            msg_send![
+            track_view,
+            initWithRect: bounds
+            options: NSTrackingAreaOptions::NSTrackingActiveAlways
+            | NSTrackingAreaOptions::NSTrackingMouseEnteredAndExited
+            | NSTrackingAreaOptions::NSTrackingMouseMoved
+            | NSTrackingAreaOptions::NSTrackingCursorUpdate
+            owner: view
+            userInfo: nil
+            ]
+        };
+        let autoresizing_mask = NSViewWidthSizable | NSViewHeightSizable;
+        let () = unsafe { msg_send![view, setAutoresizingMask: autoresizing_mask] };
+        let () = unsafe { msg_send![view, addTrackingArea: track_view] };
+    }
+
+    /// Returns the window's panel if it has already been converted to this kind.
+    ///
+    /// Fails with [`Error::AlreadyConverted`] if it has been converted to another kind.
+    pub fn existing<R: Runtime>(window: &WebviewWindow<R>) -> Result<Option<Id<Self>>, Error> {
+        let nswindow = ns_window(window)?;
+
+        if unsafe { (*nswindow).class() }.name() == K::CLASS_NAME {
+            return Ok(Some(unsafe { Id::from_ptr(nswindow as *mut Self) }));
+        }
+
+        let is_panel: BOOL = unsafe { msg_send![nswindow, isKindOfClass: class!(NSPanel)] };
+        if is_panel == YES {
+            return Err(Error::AlreadyConverted);
+        }
+
+        Ok(None)
+    }
+
+    /// Create an NSPanel from a Tauri Webview Window
+    ///
+    /// Fails with [`Error::AlreadyConverted`] if the window is already a panel.
+    pub fn from_window<R: Runtime>(window: WebviewWindow<R>) -> Result<Id<Self>, Error> {
+        if Self::existing(&window)?.is_some() {
+            return Err(Error::AlreadyConverted);
+        }
+
+        let nswindow = ns_window(&window)?;
+        let nspanel_class: id = unsafe { msg_send![Self::class(), class] };
+        unsafe {
+            object_setClass(nswindow, nspanel_class);
+            // The window is owned by tao, retain it so it outlives every handle to the panel
+            let panel = Id::from_ptr(nswindow as *mut Self);
+
+            // Add a tracking area to the panel's content view,
+            // so that we can receive mouse events such as mouseEntered and mouseExited
+            panel.add_tracking_area();
+
+            Ok(panel)
+        }
+    }
+}
+
+unsafe impl<K: PanelKind> Message for RawPanel<K> {}
+
+impl<K: PanelKind> PanelBackend for RawPanel<K> {
+    fn show(&self) {
+        RawPanel::show(self)
+    }
+
+    fn is_visible(&self) -> bool {
+        RawPanel::is_visible(self)
+    }
+
+    fn set_can_become_key_window(&self, flag: bool) {
+        RawPanel::set_can_become_key_window(self, flag)
+    }
+
+    fn set_can_become_main_window(&self, flag: bool) {
+        RawPanel::set_can_become_main_window(self, flag)
+    }
+
+    fn make_key_window(&self) {
+        RawPanel::make_key_window(self)
+    }
+
+    fn resign_key_window(&self) {
+        RawPanel::resign_key_window(self)
+    }
+
+    fn order_front_regardless(&self) {
+        RawPanel::order_front_regardless(self)
+    }
+
+    fn order_front(&self) {
+        RawPanel::order_front(self, None)
+    }
+
+    fn order_out(&self) {
+        RawPanel::order_out(self, None)
+    }
+
+    fn set_level(&self, level: PanelLevel) {
+        RawPanel::set_level(self, level.value())
+    }
+
+    fn level(&self) -> PanelLevel {
+        PanelLevel::from_value(RawPanel::level(self))
+    }
+
+    fn set_alpha_value(&self, value: f64) {
+        RawPanel::set_alpha_value(self, value)
+    }
+
+    fn alpha_value(&self) -> f64 {
+        RawPanel::alpha_value(self)
+    }
+
+    fn set_content_size(&self, width: f64, height: f64) {
+        RawPanel::set_content_size(self, width, height)
+    }
+
+    fn set_frame(&self, frame: Rect, animate: bool) {
+        RawPanel::set_frame_animated(self, frame.into(), true, animate)
+    }
+
+    fn frame(&self) -> Rect {
+        RawPanel::frame(self).into()
+    }
+
+    fn animate(
+        &self,
+        frame: Rect,
+        alpha: f64,
+        duration: Duration,
+        easing: Easing,
+        completion: Box<dyn FnOnce() + Send>,
+    ) {
+        RawPanel::animate(
+            self,
+            frame.into(),
+            alpha,
+            duration.as_secs_f64(),
+            easing.timing_function_name(),
+            completion,
+        )
+    }
+
+    fn screen(&self) -> Option<Screen> {
+        let screen = RawPanel::screen(self);
+
+        if screen == nil {
+            None
+        } else {
+            Some(unsafe { Screen::from_native(screen) })
+        }
+    }
+
+    fn screens(&self) -> Vec<Screen> {
+        Screen::all()
+    }
+
+    fn mouse_location(&self) -> Point {
+        let location: NSPoint = unsafe { msg_send![class!(NSEvent), mouseLocation] };
+        location.into()
+    }
+
+    fn key_window_screen(&self) -> Option<Screen> {
+        unsafe {
+            let app: id = msg_send![class!(NSApplication), sharedApplication];
+            let window: id = msg_send![app, keyWindow];
+            if window == nil {
+                return None;
+            }
+
+            let screen: id = msg_send![window, screen];
+            if screen == nil {
+                None
+            } else {
+                Some(Screen::from_native(screen))
+            }
+        }
+    }
+
+    fn set_style_mask(&self, style_mask: PanelStyleMask) {
+        RawPanel::set_style_mask(self, style_mask.bits() as NSUInteger)
+    }
+
+    fn style_mask(&self) -> PanelStyleMask {
+        PanelStyleMask::from_bits_retain(RawPanel::style_mask(self) as u64)
+    }
+
+    fn set_collection_behaviour(&self, behaviour: CollectionBehavior) {
+        RawPanel::set_collection_behaviour(self, behaviour.into())
+    }
+
+    fn set_floating_panel(&self, flag: bool) {
+        RawPanel::set_floating_panel(self, flag)
+    }
+
+    fn set_accepts_mouse_moved_events(&self, flag: bool) {
+        RawPanel::set_accepts_mouse_moved_events(self, flag)
+    }
+
+    fn set_ignores_mouse_events(&self, flag: bool) {
+        RawPanel::set_ignores_mouse_events(self, flag)
+    }
+
+    fn set_hides_on_deactivate(&self, flag: bool) {
+        RawPanel::set_hides_on_deactivate(self, flag)
+    }
+
+    fn set_movable_by_window_background(&self, flag: bool) {
+        RawPanel::set_movable_by_window_background(self, flag)
+    }
+
+    fn set_delegate(&self, delegate: id) {
+        crate::delegate::keep_original(self.as_id(), delegate);
+        let _: () = unsafe { msg_send![self, setDelegate: delegate] };
+        retain_delegate(self.as_id(), delegate);
+    }
+
+    fn released_when_closed(&self, flag: bool) {
+        RawPanel::released_when_closed(self, flag)
+    }
+
+    fn close(&self) {
+        RawPanel::close(self)
+    }
+}
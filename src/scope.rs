@@ -0,0 +1,118 @@
+//! Restricts which panels the commands may drive.
+//!
+//! Capabilities scope the command permissions by label:
+//!
+//! ```json
+//! {
+//!   "identifier": "nspanel:allow-close",
+//!   "allow": [{ "label": "settings" }],
+//!   "deny": [{ "label": "hud-*" }]
+//! }
+//! ```
+
+use serde::Deserialize;
+
+use crate::Error;
+
+/// A scope entry of the panel commands, `build.rs` generates its schema.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub(crate) struct PanelScope {
+    /// The label of a panel, `*` matches any run of characters.
+    pub label: String,
+}
+
+impl PanelScope {
+    fn matches(&self, label: &str) -> bool {
+        matches(&self.label, label)
+    }
+}
+
+/// Checks that the scope entries of a command let it drive the panel `label`.
+///
+/// Denied entries win. Commands allowed without any entry may drive every panel, otherwise
+/// the label must match one of the allowed entries.
+pub(crate) fn check<'a>(
+    label: &str,
+    allows: impl IntoIterator<Item = &'a PanelScope>,
+    denies: impl IntoIterator<Item = &'a PanelScope>,
+) -> Result<(), Error> {
+    if denies.into_iter().any(|entry| entry.matches(label)) {
+        return Err(Error::PanelNotAllowed);
+    }
+
+    let mut allows = allows.into_iter().peekable();
+    if allows.peek().is_none() || allows.any(|entry| entry.matches(label)) {
+        Ok(())
+    } else {
+        Err(Error::PanelNotAllowed)
+    }
+}
+
+/// Matches `label` against `pattern`, in which `*` matches any run of characters.
+fn matches(pattern: &str, label: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut rest) = label.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts: Vec<_> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard
+        return rest.is_empty();
+    };
+
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scope;
+
+    #[test]
+    fn wildcards_match_any_run_of_characters() {
+        assert!(matches("settings", "settings"));
+        assert!(!matches("settings", "settings-2"));
+        assert!(matches("hud-*", "hud-"));
+        assert!(matches("hud-*", "hud-clock"));
+        assert!(!matches("hud-*", "main"));
+        assert!(matches("*", "anything"));
+        assert!(matches("*-panel", "menu-bar-panel"));
+        assert!(matches("a*b*c", "a-b-b-c"));
+        assert!(!matches("a*bc", "abc-c"));
+        assert!(!matches("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn unscoped_commands_drive_every_panel() {
+        assert_eq!(check("main", [], []), Ok(()));
+    }
+
+    #[test]
+    fn allowed_entries_restrict_the_labels() {
+        let allows = [scope("settings"), scope("hud-*")];
+
+        assert_eq!(check("settings", &allows, []), Ok(()));
+        assert_eq!(check("hud-clock", &allows, []), Ok(()));
+        assert_eq!(check("main", &allows, []), Err(Error::PanelNotAllowed));
+    }
+
+    #[test]
+    fn denied_entries_win() {
+        let allows = [scope("*")];
+        let denies = [scope("main")];
+
+        assert_eq!(check("settings", &allows, &denies), Ok(()));
+        assert_eq!(check("main", &allows, &denies), Err(Error::PanelNotAllowed));
+        assert_eq!(check("main", [], &denies), Err(Error::PanelNotAllowed));
+    }
+}
@@ -0,0 +1,239 @@
+use serde::Deserialize;
+
+use crate::{backend::PanelBackend, Point, Rect, Screen};
+
+/// The screen [`Panel::show_on`](crate::Panel::show_on) brings a panel to.
+///
+/// From the guest JavaScript API, targets are given as `"mouse"`, `"keyWindow"`,
+/// `"mainScreen"` or `{ "screen": id }`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShowTarget {
+    /// The screen under the mouse cursor.
+    Mouse,
+    /// The screen of the app's key window.
+    KeyWindow,
+    /// The screen holding the menu bar.
+    MainScreen,
+    /// The screen with the given [`Screen::id`].
+    Screen(u32),
+}
+
+impl ShowTarget {
+    /// The targeted screen, `None` if it is not connected.
+    pub(crate) fn resolve(self, panel: &dyn PanelBackend, screens: &[Screen]) -> Option<Screen> {
+        match self {
+            Self::Mouse => screen_at(panel.mouse_location(), screens),
+            Self::KeyWindow => panel.key_window_screen(),
+            Self::MainScreen => screens.first().copied(),
+            Self::Screen(id) => screens.iter().find(|screen| screen.id == id).copied(),
+        }
+    }
+}
+
+/// The screen whose frame contains `point`.
+pub(crate) fn screen_at(point: Point, screens: &[Screen]) -> Option<Screen> {
+    screens
+        .iter()
+        .find(|screen| {
+            let frame = screen.frame;
+            (frame.origin.x..=frame.max_x()).contains(&point.x)
+                && (frame.origin.y..=frame.max_y()).contains(&point.y)
+        })
+        .copied()
+}
+
+/// Moves `frame` from the visible frame `from` to the visible frame `to`, keeping its place
+/// relative to the screen: a centered panel stays centered, one in the top third stays there.
+///
+/// The place is measured in the room the panel has to move around, so a panel touching a side
+/// of `from` touches the same side of `to`. A panel larger than `from` is centered on `to`.
+pub(crate) fn relocated(frame: Rect, from: &Rect, to: &Rect) -> Rect {
+    let x = relocated_axis(
+        frame.origin.x,
+        frame.size.width,
+        (from.origin.x, from.size.width),
+        (to.origin.x, to.size.width),
+    );
+    let y = relocated_axis(
+        frame.origin.y,
+        frame.size.height,
+        (from.origin.y, from.size.height),
+        (to.origin.y, to.size.height),
+    );
+
+    Rect::new(x, y, frame.size.width, frame.size.height)
+}
+
+fn relocated_axis(start: f64, length: f64, from: (f64, f64), to: (f64, f64)) -> f64 {
+    let room = from.1 - length;
+    let place = if room > 0.0 {
+        ((start - from.0) / room).clamp(0.0, 1.0)
+    } else {
+        0.5
+    };
+
+    to.0 + place * (to.1 - length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(id: u32, frame: Rect) -> Screen {
+        Screen {
+            id,
+            frame,
+            visible_frame: frame,
+            scale_factor: 2.0,
+        }
+    }
+
+    const MAIN: Rect = Rect {
+        origin: Point { x: 0.0, y: 0.0 },
+        size: crate::Size {
+            width: 1440.0,
+            height: 900.0,
+        },
+    };
+
+    const RIGHT: Rect = Rect {
+        origin: Point {
+            x: 1440.0,
+            y: -180.0,
+        },
+        size: crate::Size {
+            width: 1920.0,
+            height: 1080.0,
+        },
+    };
+
+    #[test]
+    fn centered_panels_stay_centered() {
+        let frame = Rect::new(570.0, 350.0, 300.0, 200.0);
+
+        assert_eq!(
+            relocated(frame, &MAIN, &RIGHT),
+            Rect::new(2250.0, 260.0, 300.0, 200.0)
+        );
+        assert_eq!(
+            relocated(relocated(frame, &MAIN, &RIGHT), &RIGHT, &MAIN),
+            frame
+        );
+    }
+
+    #[test]
+    fn panels_keep_their_place_relative_to_the_screen() {
+        // Three quarters of the way up, touching the left side
+        let frame = Rect::new(0.0, 525.0, 300.0, 200.0);
+
+        assert_eq!(
+            relocated(frame, &MAIN, &RIGHT),
+            Rect::new(1440.0, 480.0, 300.0, 200.0)
+        );
+    }
+
+    #[test]
+    fn panels_outside_the_screen_are_brought_inside() {
+        let frame = Rect::new(-100.0, 1000.0, 300.0, 200.0);
+
+        assert_eq!(
+            relocated(frame, &MAIN, &RIGHT),
+            Rect::new(1440.0, 700.0, 300.0, 200.0)
+        );
+    }
+
+    #[test]
+    fn panels_larger_than_the_screen_are_centered() {
+        let frame = Rect::new(0.0, 0.0, 1600.0, 1000.0);
+
+        assert_eq!(
+            relocated(frame, &MAIN, &RIGHT),
+            Rect::new(1600.0, -140.0, 1600.0, 1000.0)
+        );
+    }
+
+    #[test]
+    fn points_are_on_the_screen_containing_them() {
+        let screens = [screen(1, MAIN), screen(2, RIGHT)];
+        let at = |x, y| screen_at(Point::new(x, y), &screens).map(|screen| screen.id);
+
+        assert_eq!(at(100.0, 100.0), Some(1));
+        // The mouse can rest on the top edge of the screen
+        assert_eq!(at(100.0, 900.0), Some(1));
+        assert_eq!(at(2000.0, -100.0), Some(2));
+        assert_eq!(at(100.0, -100.0), None);
+    }
+
+    #[test]
+    fn targets_deserialize_from_names_and_ids() {
+        let target = |value| serde_json::from_value::<ShowTarget>(value).unwrap();
+
+        assert_eq!(target(serde_json::json!("mouse")), ShowTarget::Mouse);
+        assert_eq!(
+            target(serde_json::json!("keyWindow")),
+            ShowTarget::KeyWindow
+        );
+        assert_eq!(
+            target(serde_json::json!("mainScreen")),
+            ShowTarget::MainScreen
+        );
+        assert_eq!(
+            target(serde_json::json!({ "screen": 3 })),
+            ShowTarget::Screen(3)
+        );
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn panels_are_shown_on_the_targeted_screen() {
+        use crate::{
+            test_util,
+            testing::{app, main_screen, window},
+            WebviewWindowExt,
+        };
+
+        let app = app(test_util::init());
+        let panel = window(&app, "spotlight").to_panel().unwrap();
+        let fake = test_util::fake_panel(&app, "spotlight").unwrap();
+        test_util::set_screens(
+            &app,
+            vec![
+                main_screen(),
+                Screen {
+                    id: 2,
+                    frame: Rect::new(1440.0, 0.0, 1920.0, 1080.0),
+                    visible_frame: Rect::new(1440.0, 0.0, 1920.0, 1080.0),
+                    scale_factor: 1.0,
+                },
+            ],
+        );
+        panel.order_out();
+        // Centered on the main screen
+        panel.set_frame(Rect::new(570.0, 337.5, 300.0, 200.0), false);
+        fake.update_state(|state| state.mouse_location = Point::new(2000.0, 500.0));
+
+        panel.show_on(ShowTarget::Mouse).unwrap();
+
+        fake.assert_visible();
+        assert_eq!(panel.frame(), Rect::new(2250.0, 440.0, 300.0, 200.0));
+
+        // Already on the targeted screen
+        fake.clear_calls();
+        panel.show_on(ShowTarget::Screen(2)).unwrap();
+        fake.assert_not_called(&test_util::PanelCall::SetFrame(
+            Rect::new(2250.0, 440.0, 300.0, 200.0),
+            false,
+        ));
+
+        // Unknown screens show the panel where it is
+        panel.order_out();
+        panel.show_on(ShowTarget::Screen(3)).unwrap();
+        fake.assert_visible();
+        assert_eq!(panel.frame(), Rect::new(2250.0, 440.0, 300.0, 200.0));
+
+        fake.update_state(|state| state.key_window_screen = Some(1));
+        panel.show_on(ShowTarget::KeyWindow).unwrap();
+        assert_eq!(panel.frame(), Rect::new(570.0, 337.5, 300.0, 200.0));
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// Platform independent mirror of AppKit's
+    /// [`NSWindowStyleMask`](https://developer.apple.com/documentation/appkit/nswindow/stylemask).
+    ///
+    /// The bit values match AppKit, so converting to the native type is lossless.
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct PanelStyleMask: u64 {
+        const TITLED = 1 << 0;
+        const CLOSABLE = 1 << 1;
+        const MINIATURIZABLE = 1 << 2;
+        const RESIZABLE = 1 << 3;
+        const UTILITY_WINDOW = 1 << 4;
+        const DOC_MODAL_WINDOW = 1 << 6;
+        const NONACTIVATING_PANEL = 1 << 7;
+        const UNIFIED_TITLE_AND_TOOLBAR = 1 << 12;
+        const HUD_WINDOW = 1 << 13;
+        const FULL_SCREEN = 1 << 14;
+        const FULL_SIZE_CONTENT_VIEW = 1 << 15;
+    }
+}
+
+impl PanelStyleMask {
+    /// `NSWindowStyleMaskBorderless`, a window without any decoration.
+    pub const BORDERLESS: Self = Self::empty();
+
+    /// Checks the mask for combinations AppKit rejects for panels.
+    pub fn validate(self) -> Result<Self, InvalidStyleMask> {
+        if self.contains(Self::HUD_WINDOW) && !self.contains(Self::UTILITY_WINDOW) {
+            return Err(InvalidStyleMask::HudWithoutUtility);
+        }
+
+        if self.contains(Self::FULL_SCREEN) {
+            return Err(InvalidStyleMask::FullScreen);
+        }
+
+        Ok(self)
+    }
+}
+
+/// A [`PanelStyleMask`] AppKit would reject.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InvalidStyleMask {
+    /// `HUD_WINDOW` is only valid together with `UTILITY_WINDOW`.
+    HudWithoutUtility,
+    /// `FULL_SCREEN` is managed by AppKit and cannot be set directly.
+    FullScreen,
+}
+
+impl fmt::Display for InvalidStyleMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HudWithoutUtility => f.write_str("a HUD panel must also be a utility window"),
+            Self::FullScreen => f.write_str("the full screen style is managed by AppKit"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidStyleMask {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits_match_appkit() {
+        assert_eq!(PanelStyleMask::BORDERLESS.bits(), 0);
+        assert_eq!(PanelStyleMask::RESIZABLE.bits(), 8);
+        assert_eq!(PanelStyleMask::NONACTIVATING_PANEL.bits(), 128);
+        assert_eq!(PanelStyleMask::HUD_WINDOW.bits(), 8192);
+        assert_eq!(PanelStyleMask::FULL_SIZE_CONTENT_VIEW.bits(), 32768);
+    }
+
+    #[test]
+    fn hud_requires_utility() {
+        assert_eq!(
+            PanelStyleMask::HUD_WINDOW.validate(),
+            Err(InvalidStyleMask::HudWithoutUtility)
+        );
+
+        let hud =
+            PanelStyleMask::HUD_WINDOW | PanelStyleMask::UTILITY_WINDOW | PanelStyleMask::TITLED;
+        assert_eq!(hud.validate(), Ok(hud));
+    }
+
+    #[test]
+    fn full_screen_cannot_be_set() {
+        assert_eq!(
+            (PanelStyleMask::TITLED | PanelStyleMask::FULL_SCREEN).validate(),
+            Err(InvalidStyleMask::FullScreen)
+        );
+    }
+
+    #[test]
+    fn common_panel_masks_are_valid() {
+        for mask in [
+            PanelStyleMask::BORDERLESS,
+            PanelStyleMask::NONACTIVATING_PANEL,
+            PanelStyleMask::NONACTIVATING_PANEL | PanelStyleMask::RESIZABLE,
+            PanelStyleMask::TITLED
+                | PanelStyleMask::CLOSABLE
+                | PanelStyleMask::FULL_SIZE_CONTENT_VIEW,
+        ] {
+            assert_eq!(mask.validate(), Ok(mask));
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! Helpers for testing code that drives panels.
+//!
+//! Register [`init`] instead of [`crate::init`] on a [`tauri::test::mock_builder`] app and every
+//! window converted with [`WebviewWindowExt`](crate::WebviewWindowExt) is backed by a
+//! [`FakePanel`]. Fetch it with [`fake_panel`] to assert on what your code did:
+//!
+//! ```no_run
+//! use tauri::test::{mock_builder, mock_context, noop_assets};
+//! use tauri::{WebviewUrl, WebviewWindowBuilder};
+//! use tauri_nspanel::{test_util, ManagerExt, WebviewWindowExt};
+//!
+//! let app = mock_builder()
+//!     .plugin(test_util::init())
+//!     .build(mock_context(noop_assets()))
+//!     .unwrap();
+//! let window = WebviewWindowBuilder::new(&app, "main", WebviewUrl::default())
+//!     .build()
+//!     .unwrap();
+//! window.to_panel().unwrap();
+//!
+//! app.get_webview_panel("main").unwrap().order_out();
+//!
+//! let fake = test_util::fake_panel(&app, "main").unwrap();
+//! fake.assert_hidden();
+//! fake.assert_calls(&[test_util::PanelCall::OrderOut]);
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+#[cfg(target_os = "macos")]
+use cocoa::base::id;
+use tauri::{plugin::TauriPlugin, Manager, Runtime, WebviewWindow};
+
+use crate::{
+    backend::PanelBackend,
+    kind::PanelKind,
+    memory_panel::{MemoryPanel, MemoryPanelState},
+    Backend, CollectionBehavior, Easing, Panel, PanelEvent, PanelLevel, PanelStyleMask, Point,
+    Rect, Screen,
+};
+
+/// A call made on a [`FakePanel`], with its arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PanelCall {
+    Show,
+    IsVisible,
+    SetCanBecomeKeyWindow(bool),
+    SetCanBecomeMainWindow(bool),
+    MakeKeyWindow,
+    ResignKeyWindow,
+    OrderFrontRegardless,
+    OrderFront,
+    OrderOut,
+    SetLevel(PanelLevel),
+    Level,
+    SetAlphaValue(f64),
+    AlphaValue,
+    SetContentSize(f64, f64),
+    SetFrame(Rect, bool),
+    Frame,
+    Animate(Rect, f64, Duration, Easing),
+    Screen,
+    Screens,
+    MouseLocation,
+    KeyWindowScreen,
+    SetStyleMask(PanelStyleMask),
+    StyleMask,
+    SetCollectionBehaviour(CollectionBehavior),
+    SetFloatingPanel(bool),
+    SetAcceptsMouseMovedEvents(bool),
+    SetIgnoresMouseEvents(bool),
+    SetHidesOnDeactivate(bool),
+    SetMovableByWindowBackground(bool),
+    SetDelegate,
+    ReleasedWhenClosed(bool),
+    Close,
+}
+
+/// A panel that records every call made through the panel API.
+///
+/// State changes are applied to an inner [`MemoryPanel`], so queries such as
+/// [`is_visible`](PanelBackend::is_visible) answer the way the native panel would.
+///
+/// Animations run until [`FakePanel::finish_animations`] is called.
+#[derive(Debug, Default)]
+pub struct FakePanel {
+    panel: MemoryPanel,
+    calls: Mutex<Vec<PanelCall>>,
+    animations: Mutex<Vec<RunningAnimation>>,
+}
+
+/// An animation of a [`FakePanel`] waiting for [`FakePanel::finish_animations`].
+struct RunningAnimation {
+    frame: Rect,
+    alpha: f64,
+    completion: Box<dyn FnOnce() + Send>,
+}
+
+impl fmt::Debug for RunningAnimation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RunningAnimation")
+            .field("frame", &self.frame)
+            .field("alpha", &self.alpha)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FakePanel {
+    pub fn new(panel: MemoryPanel) -> Self {
+        Self {
+            panel,
+            calls: Mutex::default(),
+            animations: Mutex::default(),
+        }
+    }
+
+    /// Ends the running animations in the order they started, the way AppKit does once their
+    /// duration is over: the panel takes the frame and alpha of the last one, then every
+    /// completion is called.
+    pub fn finish_animations(&self) {
+        let animations = std::mem::take(&mut *self.lock_animations());
+
+        for animation in &animations {
+            self.panel.update(|state| {
+                state.frame = animation.frame;
+                state.alpha = animation.alpha;
+            });
+        }
+
+        for animation in animations {
+            (animation.completion)();
+        }
+    }
+
+    /// Every call made so far, in order.
+    pub fn calls(&self) -> Vec<PanelCall> {
+        self.lock_calls().clone()
+    }
+
+    pub fn clear_calls(&self) {
+        self.lock_calls().clear();
+    }
+
+    pub fn state(&self) -> MemoryPanelState {
+        self.panel.state()
+    }
+
+    /// Replaces the screens this panel reports, see [`set_screens`] to set them for every
+    /// fake panel.
+    pub fn set_screens(&self, screens: Vec<Screen>) {
+        self.panel.set_screens(screens);
+    }
+
+    /// Changes the state without recording a call, see [`MemoryPanel::update`].
+    pub fn update_state(&self, f: impl FnOnce(&mut MemoryPanelState)) {
+        self.panel.update(f);
+    }
+
+    /// Asserts that exactly `expected` calls were made, in that order.
+    #[track_caller]
+    pub fn assert_calls(&self, expected: &[PanelCall]) {
+        assert_eq!(self.calls(), expected, "unexpected panel calls");
+    }
+
+    #[track_caller]
+    pub fn assert_called(&self, call: &PanelCall) {
+        let calls = self.calls();
+        assert!(
+            calls.contains(call),
+            "expected {call:?} to be called, got {calls:?}"
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_not_called(&self, call: &PanelCall) {
+        let calls = self.calls();
+        assert!(
+            !calls.contains(call),
+            "expected {call:?} not to be called, got {calls:?}"
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_visible(&self) {
+        assert!(self.state().visible, "expected panel to be visible");
+    }
+
+    #[track_caller]
+    pub fn assert_hidden(&self) {
+        assert!(!self.state().visible, "expected panel to be hidden");
+    }
+
+    #[track_caller]
+    pub fn assert_key(&self) {
+        assert!(self.state().key, "expected panel to be the key window");
+    }
+
+    #[track_caller]
+    pub fn assert_level(&self, level: PanelLevel) {
+        assert_eq!(self.state().level, level, "unexpected panel level");
+    }
+
+    #[track_caller]
+    pub fn assert_alpha(&self, alpha: f64) {
+        assert_eq!(self.state().alpha, alpha, "unexpected panel alpha");
+    }
+
+    #[track_caller]
+    pub fn assert_style_mask(&self, style_mask: PanelStyleMask) {
+        assert_eq!(
+            self.state().style_mask,
+            style_mask,
+            "unexpected panel style mask"
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_collection_behaviour(&self, behaviour: CollectionBehavior) {
+        assert_eq!(
+            self.state().collection_behaviour,
+            behaviour,
+            "unexpected panel collection behaviour"
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_closed(&self) {
+        assert!(self.state().closed, "expected panel to be closed");
+    }
+
+    fn record(&self, call: PanelCall) {
+        self.lock_calls().push(call);
+    }
+
+    fn lock_calls(&self) -> MutexGuard<'_, Vec<PanelCall>> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_animations(&self) -> MutexGuard<'_, Vec<RunningAnimation>> {
+        self.animations.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl PanelBackend for FakePanel {
+    fn show(&self) {
+        self.record(PanelCall::Show);
+        self.panel.show();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.record(PanelCall::IsVisible);
+        self.panel.is_visible()
+    }
+
+    fn set_can_become_key_window(&self, flag: bool) {
+        self.record(PanelCall::SetCanBecomeKeyWindow(flag));
+        self.panel.set_can_become_key_window(flag);
+    }
+
+    fn set_can_become_main_window(&self, flag: bool) {
+        self.record(PanelCall::SetCanBecomeMainWindow(flag));
+        self.panel.set_can_become_main_window(flag);
+    }
+
+    fn make_key_window(&self) {
+        self.record(PanelCall::MakeKeyWindow);
+        self.panel.make_key_window();
+    }
+
+    fn resign_key_window(&self) {
+        self.record(PanelCall::ResignKeyWindow);
+        self.panel.resign_key_window();
+    }
+
+    fn order_front_regardless(&self) {
+        self.record(PanelCall::OrderFrontRegardless);
+        self.panel.order_front_regardless();
+    }
+
+    fn order_front(&self) {
+        self.record(PanelCall::OrderFront);
+        self.panel.order_front();
+    }
+
+    fn order_out(&self) {
+        self.record(PanelCall::OrderOut);
+        self.panel.order_out();
+    }
+
+    fn set_level(&self, level: PanelLevel) {
+        self.record(PanelCall::SetLevel(level.clone()));
+        self.panel.set_level(level);
+    }
+
+    fn level(&self) -> PanelLevel {
+        self.record(PanelCall::Level);
+        self.panel.level()
+    }
+
+    fn set_alpha_value(&self, value: f64) {
+        self.record(PanelCall::SetAlphaValue(value));
+        self.panel.set_alpha_value(value);
+    }
+
+    fn alpha_value(&self) -> f64 {
+        self.record(PanelCall::AlphaValue);
+        self.panel.alpha_value()
+    }
+
+    fn set_content_size(&self, width: f64, height: f64) {
+        self.record(PanelCall::SetContentSize(width, height));
+        self.panel.set_content_size(width, height);
+    }
+
+    fn set_frame(&self, frame: Rect, animate: bool) {
+        self.record(PanelCall::SetFrame(frame, animate));
+        self.panel.set_frame(frame, animate);
+    }
+
+    fn frame(&self) -> Rect {
+        self.record(PanelCall::Frame);
+        self.panel.frame()
+    }
+
+    fn animate(
+        &self,
+        frame: Rect,
+        alpha: f64,
+        duration: Duration,
+        easing: Easing,
+        completion: Box<dyn FnOnce() + Send>,
+    ) {
+        self.record(PanelCall::Animate(frame, alpha, duration, easing));
+        self.lock_animations().push(RunningAnimation {
+            frame,
+            alpha,
+            completion,
+        });
+    }
+
+    fn screen(&self) -> Option<Screen> {
+        self.record(PanelCall::Screen);
+        self.panel.screen()
+    }
+
+    fn screens(&self) -> Vec<Screen> {
+        self.record(PanelCall::Screens);
+        self.panel.screens()
+    }
+
+    fn mouse_location(&self) -> Point {
+        self.record(PanelCall::MouseLocation);
+        self.panel.mouse_location()
+    }
+
+    fn key_window_screen(&self) -> Option<Screen> {
+        self.record(PanelCall::KeyWindowScreen);
+        self.panel.key_window_screen()
+    }
+
+    fn set_style_mask(&self, style_mask: PanelStyleMask) {
+        self.record(PanelCall::SetStyleMask(style_mask));
+        self.panel.set_style_mask(style_mask);
+    }
+
+    fn style_mask(&self) -> PanelStyleMask {
+        self.record(PanelCall::StyleMask);
+        self.panel.style_mask()
+    }
+
+    fn set_collection_behaviour(&self, behaviour: CollectionBehavior) {
+        self.record(PanelCall::SetCollectionBehaviour(behaviour));
+        self.panel.set_collection_behaviour(behaviour);
+    }
+
+    fn set_floating_panel(&self, flag: bool) {
+        self.record(PanelCall::SetFloatingPanel(flag));
+        self.panel.set_floating_panel(flag);
+    }
+
+    fn set_accepts_mouse_moved_events(&self, flag: bool) {
+        self.record(PanelCall::SetAcceptsMouseMovedEvents(flag));
+        self.panel.set_accepts_mouse_moved_events(flag);
+    }
+
+    fn set_ignores_mouse_events(&self, flag: bool) {
+        self.record(PanelCall::SetIgnoresMouseEvents(flag));
+        self.panel.set_ignores_mouse_events(flag);
+    }
+
+    fn set_hides_on_deactivate(&self, flag: bool) {
+        self.record(PanelCall::SetHidesOnDeactivate(flag));
+        self.panel.set_hides_on_deactivate(flag);
+    }
+
+    fn set_movable_by_window_background(&self, flag: bool) {
+        self.record(PanelCall::SetMovableByWindowBackground(flag));
+        self.panel.set_movable_by_window_background(flag);
+    }
+
+    #[cfg(target_os = "macos")]
+    fn set_delegate(&self, delegate: id) {
+        self.record(PanelCall::SetDelegate);
+        self.panel.set_delegate(delegate);
+    }
+
+    fn released_when_closed(&self, flag: bool) {
+        self.record(PanelCall::ReleasedWhenClosed(flag));
+        self.panel.released_when_closed(flag);
+    }
+
+    fn close(&self) {
+        self.record(PanelCall::Close);
+        self.panel.close();
+    }
+}
+
+/// Fake panels created by the plugin returned from [`init`], by window label.
+#[derive(Default)]
+struct FakePanels(Mutex<HashMap<String, Arc<FakePanel>>>);
+
+/// The screens reported by fake panels, see [`set_screens`].
+#[derive(Default)]
+struct FakeScreens(Mutex<Vec<Screen>>);
+
+/// Initializes the plugin with [`FakePanel`] backed panels.
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<crate::Config>> {
+    crate::plugin(Backend::Fake)
+}
+
+/// Returns the fake panel created for the window with the given label.
+pub fn fake_panel<R: Runtime, M: Manager<R>>(manager: &M, label: &str) -> Option<Arc<FakePanel>> {
+    let fakes = manager.try_state::<FakePanels>()?;
+    let fakes = fakes.0.lock().unwrap_or_else(|e| e.into_inner());
+    fakes.get(label).cloned()
+}
+
+/// Sets the screens reported by every fake panel, existing or created later, the one holding
+/// the menu bar first.
+///
+/// Fake panels start without any screen.
+pub fn set_screens<R: Runtime, M: Manager<R>>(manager: &M, screens: Vec<Screen>) {
+    if manager.try_state::<FakeScreens>().is_none() {
+        manager.manage(FakeScreens::default());
+    }
+
+    *manager
+        .state::<FakeScreens>()
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = screens.clone();
+
+    if let Some(fakes) = manager.try_state::<FakePanels>() {
+        for fake in fakes.0.lock().unwrap_or_else(|e| e.into_inner()).values() {
+            fake.set_screens(screens.clone());
+        }
+    }
+}
+
+/// Delivers `event` to the panel's [subscribers](crate::Panel::on_event) as if its delegate had
+/// received it.
+pub fn emit_event(panel: &Panel, event: PanelEvent) {
+    panel.emit(&event);
+}
+
+pub(crate) fn fake_backend<K: PanelKind, R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Arc<dyn PanelBackend> {
+    let fake = Arc::new(FakePanel::new(MemoryPanel::from_window::<K, R>(
+        window.to_owned(),
+    )));
+
+    if let Some(screens) = window.try_state::<FakeScreens>() {
+        fake.set_screens(screens.0.lock().unwrap_or_else(|e| e.into_inner()).clone());
+    }
+
+    if window.try_state::<FakePanels>().is_none() {
+        window.manage(FakePanels::default());
+    }
+
+    window
+        .state::<FakePanels>()
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(window.label().into(), fake.clone());
+
+    fake
+}
@@ -0,0 +1,57 @@
+//! Mock apps and windows shared by the tests of the plugin's modules.
+
+// Not every helper is used without the `test-util` feature or on every platform
+#![allow(dead_code)]
+
+use tauri::{
+    plugin::TauriPlugin,
+    test::{mock_builder, mock_context, noop_assets, MockRuntime},
+    App, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::{scope::PanelScope, Config, Rect, Screen};
+
+pub(crate) fn app(plugin: TauriPlugin<MockRuntime, Option<Config>>) -> App<MockRuntime> {
+    mock_builder()
+        .plugin(plugin)
+        .build(mock_context(noop_assets()))
+        .unwrap()
+}
+
+/// An app whose `plugins.nspanel` configuration is `config`.
+pub(crate) fn configured_app(
+    plugin: TauriPlugin<MockRuntime, Option<Config>>,
+    config: serde_json::Value,
+) -> tauri::Result<App<MockRuntime>> {
+    let mut context = mock_context(noop_assets());
+    context
+        .config_mut()
+        .plugins
+        .0
+        .insert("nspanel".into(), config);
+
+    mock_builder().plugin(plugin).build(context)
+}
+
+pub(crate) fn window(app: &App<MockRuntime>, label: &str) -> WebviewWindow<MockRuntime> {
+    WebviewWindowBuilder::new(app, label, WebviewUrl::default())
+        .build()
+        .unwrap()
+}
+
+/// A 1440x900 screen holding the menu bar, 25 points of which are taken by the menu bar.
+pub(crate) fn main_screen() -> Screen {
+    Screen {
+        id: 1,
+        frame: Rect::new(0.0, 0.0, 1440.0, 900.0),
+        visible_frame: Rect::new(0.0, 0.0, 1440.0, 875.0),
+        scale_factor: 2.0,
+    }
+}
+
+/// A scope entry for the panels labelled `label`.
+pub(crate) fn scope(label: &str) -> PanelScope {
+    PanelScope {
+        label: label.into(),
+    }
+}